@@ -0,0 +1,170 @@
+//! Multiset operations for maps used as counters.
+
+use std::convert::TryInto;
+use std::iter;
+use std::ops::{AddAssign, SubAssign};
+
+use crate::num::{One, Zero};
+use crate::{ArithMap, Join, Storage};
+
+impl<K, V, S> ArithMap<K, V, S>
+where
+    S: Storage<K, V>,
+{
+    /// Counts how many times each key occurs.
+    ///
+    /// ```
+    /// # use arith::*;
+    /// let x: ArithMap<_, i32> = ArithMap::from_keys("hello".chars());
+    ///
+    /// assert_eq!(x, arithmap!{'h' => 1, 'e' => 1, 'l' => 2, 'o' => 1});
+    /// ```
+    pub fn from_keys<I>(keys: I) -> Self
+    where
+        I: IntoIterator<Item = K>,
+        V: AddAssign + One,
+    {
+        keys.into_iter().map(|k| (k, V::one())).collect()
+    }
+
+    /// ```
+    /// # use arith::*;
+    /// let x = arithmap!{"a" => 3, "b" => 1};
+    ///
+    /// assert_eq!(x.total(), 4);
+    /// ```
+    pub fn total(&self) -> V
+    where
+        V: for<'x> AddAssign<&'x V> + Zero,
+    {
+        let mut total = V::zero();
+        for (_, v) in self.storage.iter() {
+            total += v;
+        }
+        total
+    }
+
+    /// The `n` largest counts in descending order; equal counts keep the map's iteration
+    /// order.
+    ///
+    /// ```
+    /// # use arith::*;
+    /// let x = arithbtreemap!{"a" => 1, "b" => 3, "c" => 2, "d" => 3};
+    ///
+    /// assert_eq!(x.most_common(3), vec![(&"b", &3), (&"d", &3), (&"c", &2)]);
+    /// ```
+    pub fn most_common(&self, n: usize) -> Vec<(&K, &V)>
+    where
+        V: Ord,
+    {
+        let mut items: Vec<_> = self.storage.iter().collect();
+        items.sort_by(|(_, v1), (_, v2)| v2.cmp(v1));
+        items.truncate(n);
+        items
+    }
+
+    /// Repeats each key as many times as its count; keys with a count below one are skipped.
+    ///
+    /// ```
+    /// # use arith::*;
+    /// let x = arithbtreemap!{"a" => 2, "b" => 0, "c" => -1, "d" => 1};
+    ///
+    /// assert_eq!(x.elements().collect::<Vec<_>>(), vec![&"a", &"a", &"d"]);
+    /// ```
+    pub fn elements(&self) -> impl Iterator<Item = &K>
+    where
+        V: Clone + TryInto<usize>,
+    {
+        self.storage
+            .iter()
+            .flat_map(|(k, v)| iter::repeat(k).take(v.clone().try_into().unwrap_or(0)))
+    }
+
+    /// Multiset union: the larger count of each key, keeping only positive counts.
+    ///
+    /// ```
+    /// # use arith::*;
+    /// let x = arithmap!{"a" => 3, "b" => 1};
+    /// let y = arithmap!{"b" => 2, "c" => 1};
+    ///
+    /// assert_eq!(x.union(y), arithmap!{"a" => 3, "b" => 2, "c" => 1});
+    /// ```
+    pub fn union(self, other: Self) -> Self
+    where
+        V: Clone + PartialOrd + Zero,
+    {
+        let max = |v1, v2| if v1 >= v2 { v1 } else { v2 };
+        let mut union = self.combine(other, Join::outer(V::zero()), max);
+        union.keep_positive();
+        union
+    }
+
+    /// Multiset intersection: the smaller count of keys present in both, keeping only
+    /// positive counts.
+    ///
+    /// ```
+    /// # use arith::*;
+    /// let x = arithmap!{"a" => 3, "b" => 1};
+    /// let y = arithmap!{"a" => 2, "b" => 2, "c" => 1};
+    ///
+    /// assert_eq!(x.intersection(y), arithmap!{"a" => 2, "b" => 1});
+    /// ```
+    pub fn intersection(self, other: Self) -> Self
+    where
+        V: Clone + PartialOrd + Zero,
+    {
+        let min = |v1, v2| if v1 <= v2 { v1 } else { v2 };
+        let mut intersection = self.combine(other, Join::Inner, min);
+        intersection.keep_positive();
+        intersection
+    }
+
+    /// Subtraction that drops every key whose count would fall to zero or below.
+    ///
+    /// ```
+    /// # use arith::*;
+    /// let x = arithmap!{"a" => 3, "b" => 1};
+    /// let y = arithmap!{"a" => 1, "b" => 2, "c" => 1};
+    ///
+    /// assert_eq!(x.truncating_sub(y), arithmap!{"a" => 2});
+    /// ```
+    ///
+    /// Counts never go below zero on the way, so unsigned counts don't overflow:
+    ///
+    /// ```
+    /// # use arith::*;
+    /// let x = arithmap!{"a" => 3u32, "b" => 1};
+    /// let y = arithmap!{"a" => 1, "b" => 2, "c" => 1};
+    ///
+    /// assert_eq!(x.truncating_sub(y), arithmap!{"a" => 2});
+    /// ```
+    pub fn truncating_sub(mut self, other: Self) -> Self
+    where
+        V: for<'x> SubAssign<&'x V> + PartialOrd + Zero,
+    {
+        self.storage.retain(|k, v1| match other.storage.get(k) {
+            Some(v2) if *v1 > *v2 => {
+                *v1 -= v2;
+                true
+            }
+            Some(_) => false,
+            None => *v1 > V::zero(),
+        });
+        self.storage.tidy();
+        self
+    }
+
+    /// Clamps negative counts to zero and prunes them.
+    fn keep_positive(&mut self)
+    where
+        V: PartialOrd + Zero,
+    {
+        for (_, v) in self.storage.iter_mut() {
+            if *v < V::zero() {
+                *v = V::zero();
+            }
+        }
+        self.prune();
+        self.storage.tidy();
+    }
+}
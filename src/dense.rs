@@ -0,0 +1,386 @@
+//! Dense vectors, for index-keyed data where most keys are present.
+
+use std::iter::FromIterator;
+use std::ops::{Add, AddAssign, Sub, SubAssign, Mul, MulAssign, Div, DivAssign, Rem, RemAssign, Neg};
+use std::ops::{Index, IndexMut};
+use std::slice;
+use std::vec;
+
+use crate::num::Zero;
+use crate::{ArithMap, Storage};
+
+/// A `Vec` with the operators of [`ArithMap`], positions standing in for keys.
+///
+/// Element-wise operations treat positions past the end of the shorter vector as missing
+/// keys: `+` and `-` extend the result, while `*`, `/` and `%` truncate it.
+///
+/// ```
+/// # use arith::*;
+/// let x = arithvec![1, 2, 3];
+/// let y = arithvec![10, 20];
+///
+/// assert_eq!(&x + &y, arithvec![11, 22, 3]);
+/// assert_eq!(&y - &x, arithvec![9, 18, -3]);
+/// assert_eq!(&x * &y, arithvec![10, 40]);
+/// assert_eq!(&y / &x, arithvec![10, 10]);
+/// assert_eq!(&x / &y, arithvec![0, 0]);
+/// assert_eq!(x * 2 + 1, arithvec![3, 5, 7]);
+/// assert_eq!(-y, arithvec![-10, -20]);
+/// ```
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ArithVec<V> {
+    pub values: Vec<V>,
+}
+
+#[macro_export(local_inner_macros)]
+/// ```
+/// # use arith::*;
+/// let x = arithvec![1, 2];
+/// assert_eq!(x.values, vec![1, 2]);
+/// ```
+macro_rules! arithvec {
+    ($($value:expr),* $(,)?) => {
+        $crate::ArithVec::from(::std::vec![$($value),*])
+    };
+}
+
+impl<V> ArithVec<V> {
+    /// ```
+    /// # use arith::*;
+    /// let x: ArithVec<i32> = ArithVec::new();
+    ///
+    /// assert!(x.is_empty());
+    /// ```
+    pub fn new() -> Self {
+        ArithVec { values: Vec::new() }
+    }
+
+    /// ```
+    /// # use arith::*;
+    /// let x = arithvec![1, 0];
+    ///
+    /// assert_eq!(x.len(), 2);
+    /// ```
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// ```
+    /// # use arith::*;
+    /// let x: ArithVec<i32> = arithvec![];
+    ///
+    /// assert!(x.is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Pairs each value with the key at the same position of `keys`; values past the end
+    /// of `keys` are dropped.
+    ///
+    /// ```
+    /// # use arith::*;
+    /// let x = arithvec![1, 0, 3];
+    /// let y: ArithMap<_, _> = x.into_map(&["a", "b", "c"]);
+    ///
+    /// assert_eq!(y, arithmap!{"a" => 1, "b" => 0, "c" => 3});
+    /// ```
+    pub fn into_map<K, S>(self, keys: &[K]) -> ArithMap<K, V, S>
+    where
+        K: Clone,
+        S: Storage<K, V>,
+    {
+        let mut storage = S::default();
+        for (k, v) in keys.iter().cloned().zip(self.values) {
+            storage.insert(k, v);
+        }
+        ArithMap::from(storage)
+    }
+}
+
+impl<K, V, S> ArithMap<K, V, S>
+where
+    S: Storage<K, V>,
+{
+    /// Values in the order of `keys`, with zero for keys missing from the map; keys not
+    /// in `keys` are dropped.
+    ///
+    /// ```
+    /// # use arith::*;
+    /// let x = arithmap!{"a" => 1, "c" => 3, "d" => 4};
+    ///
+    /// assert_eq!(x.to_dense(&["a", "b", "c"]), arithvec![1, 0, 3]);
+    /// ```
+    pub fn to_dense(&self, keys: &[K]) -> ArithVec<V>
+    where
+        V: Clone + Zero,
+    {
+        keys.iter()
+            .map(|k| self.storage.get(k).cloned().unwrap_or_else(V::zero))
+            .collect()
+    }
+}
+
+impl<V> From<Vec<V>> for ArithVec<V> {
+    fn from(values: Vec<V>) -> Self {
+        ArithVec { values }
+    }
+}
+
+/// ```
+/// # use arith::*;
+/// let x: ArithVec<_> = (1..4).collect();
+///
+/// assert_eq!(x, arithvec![1, 2, 3]);
+/// ```
+impl<V> FromIterator<V> for ArithVec<V> {
+    fn from_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = V>,
+    {
+        ArithVec::from(Vec::from_iter(iter))
+    }
+}
+
+impl<V> IntoIterator for ArithVec<V> {
+    type Item = V;
+    type IntoIter = vec::IntoIter<V>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.values.into_iter()
+    }
+}
+
+impl<'a, V> IntoIterator for &'a ArithVec<V> {
+    type Item = &'a V;
+    type IntoIter = slice::Iter<'a, V>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.values.iter()
+    }
+}
+
+impl<'a, V> IntoIterator for &'a mut ArithVec<V> {
+    type Item = &'a mut V;
+    type IntoIter = slice::IterMut<'a, V>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.values.iter_mut()
+    }
+}
+
+/// ```
+/// # use arith::*;
+/// let mut x = arithvec![1, 2];
+/// x[1] += 1;
+///
+/// assert_eq!(x[1], 3);
+/// ```
+impl<V> Index<usize> for ArithVec<V> {
+    type Output = V;
+    fn index(&self, index: usize) -> &V {
+        &self.values[index]
+    }
+}
+
+impl<V> IndexMut<usize> for ArithVec<V> {
+    fn index_mut(&mut self, index: usize) -> &mut V {
+        &mut self.values[index]
+    }
+}
+
+/// Implements `v op k` and `&v op k` on top of `v op= &k`.
+macro_rules! scalar_op {
+    (impl $imp:ident, $method:ident, $assign_imp:ident, $assign:ident) => {
+        impl<V> $assign_imp<V> for ArithVec<V>
+        where
+            V: for<'x> $assign_imp<&'x V>,
+        {
+            fn $assign(&mut self, other: V) {
+                for v in self.values.iter_mut() {
+                    v.$assign(&other);
+                }
+            }
+        }
+
+        impl<V> $imp<V> for ArithVec<V>
+        where
+            V: for<'x> $assign_imp<&'x V>,
+        {
+            type Output = Self;
+            fn $method(mut self, other: V) -> Self {
+                self.$assign(other);
+                self
+            }
+        }
+
+        impl<V> $imp<V> for &ArithVec<V>
+        where
+            V: for<'x> $assign_imp<&'x V> + Clone,
+        {
+            type Output = ArithVec<V>;
+            fn $method(self, other: V) -> ArithVec<V> {
+                let mut r = self.clone();
+                r.$assign(other);
+                r
+            }
+        }
+    };
+}
+
+scalar_op!(impl Add, add, AddAssign, add_assign);
+scalar_op!(impl Sub, sub, SubAssign, sub_assign);
+scalar_op!(impl Mul, mul, MulAssign, mul_assign);
+scalar_op!(impl Div, div, DivAssign, div_assign);
+scalar_op!(impl Rem, rem, RemAssign, rem_assign);
+
+impl<V> AddAssign<&ArithVec<V>> for ArithVec<V>
+where
+    V: for<'x> AddAssign<&'x V> + Clone,
+{
+    fn add_assign(&mut self, other: &Self) {
+        let n = self.len();
+        for (v1, v2) in self.values.iter_mut().zip(&other.values) {
+            *v1 += v2;
+        }
+        self.values.extend(other.values.iter().skip(n).cloned());
+    }
+}
+
+impl<V> SubAssign<&ArithVec<V>> for ArithVec<V>
+where
+    V: for<'x> SubAssign<&'x V> + Zero,
+{
+    fn sub_assign(&mut self, other: &Self) {
+        let n = self.len();
+        for (v1, v2) in self.values.iter_mut().zip(&other.values) {
+            *v1 -= v2;
+        }
+        for v2 in other.values.iter().skip(n) {
+            let mut v = V::zero();
+            v -= v2;
+            self.values.push(v);
+        }
+    }
+}
+
+impl<V> MulAssign<&ArithVec<V>> for ArithVec<V>
+where
+    V: for<'x> MulAssign<&'x V>,
+{
+    fn mul_assign(&mut self, other: &Self) {
+        self.values.truncate(other.len());
+        for (v1, v2) in self.values.iter_mut().zip(&other.values) {
+            *v1 *= v2;
+        }
+    }
+}
+
+impl<V> DivAssign<&ArithVec<V>> for ArithVec<V>
+where
+    V: for<'x> DivAssign<&'x V>,
+{
+    fn div_assign(&mut self, other: &Self) {
+        self.values.truncate(other.len());
+        for (v1, v2) in self.values.iter_mut().zip(&other.values) {
+            *v1 /= v2;
+        }
+    }
+}
+
+impl<V> RemAssign<&ArithVec<V>> for ArithVec<V>
+where
+    V: for<'x> RemAssign<&'x V>,
+{
+    fn rem_assign(&mut self, other: &Self) {
+        self.values.truncate(other.len());
+        for (v1, v2) in self.values.iter_mut().zip(&other.values) {
+            *v1 %= v2;
+        }
+    }
+}
+
+/// Implements `a op= b`, `a op b`, `a op &b`, `&a op b` and `&a op &b` on top of `a op= &b`.
+macro_rules! elementwise_op {
+    (impl $imp:ident, $method:ident, $assign_imp:ident, $assign:ident where $($bound:tt)*) => {
+        impl<V> $assign_imp for ArithVec<V>
+        where
+            $($bound)*
+        {
+            fn $assign(&mut self, other: Self) {
+                self.$assign(&other);
+            }
+        }
+
+        impl<V> $imp for ArithVec<V>
+        where
+            $($bound)*
+        {
+            type Output = Self;
+            fn $method(mut self, other: Self) -> Self {
+                self.$assign(&other);
+                self
+            }
+        }
+
+        impl<V> $imp<&ArithVec<V>> for ArithVec<V>
+        where
+            $($bound)*
+        {
+            type Output = Self;
+            fn $method(mut self, other: &Self) -> Self {
+                self.$assign(other);
+                self
+            }
+        }
+
+        impl<V> $imp<ArithVec<V>> for &ArithVec<V>
+        where
+            V: Clone,
+            $($bound)*
+        {
+            type Output = ArithVec<V>;
+            fn $method(self, other: ArithVec<V>) -> ArithVec<V> {
+                let mut r = self.clone();
+                r.$assign(&other);
+                r
+            }
+        }
+
+        impl<V> $imp<&ArithVec<V>> for &ArithVec<V>
+        where
+            V: Clone,
+            $($bound)*
+        {
+            type Output = ArithVec<V>;
+            fn $method(self, other: &ArithVec<V>) -> ArithVec<V> {
+                let mut r = self.clone();
+                r.$assign(other);
+                r
+            }
+        }
+    };
+}
+
+elementwise_op!(impl Add, add, AddAssign, add_assign where V: for<'x> AddAssign<&'x V> + Clone);
+elementwise_op!(impl Sub, sub, SubAssign, sub_assign where V: for<'x> SubAssign<&'x V> + Zero);
+elementwise_op!(impl Mul, mul, MulAssign, mul_assign where V: for<'x> MulAssign<&'x V>);
+elementwise_op!(impl Div, div, DivAssign, div_assign where V: for<'x> DivAssign<&'x V>);
+elementwise_op!(impl Rem, rem, RemAssign, rem_assign where V: for<'x> RemAssign<&'x V>);
+
+impl<V> Neg for ArithVec<V>
+where
+    V: Neg<Output = V>,
+{
+    type Output = Self;
+    fn neg(self) -> Self {
+        self.values.into_iter().map(Neg::neg).collect()
+    }
+}
+
+impl<V> Neg for &ArithVec<V>
+where
+    V: Neg<Output = V> + Clone,
+{
+    type Output = ArithVec<V>;
+    fn neg(self) -> ArithVec<V> {
+        -self.clone()
+    }
+}
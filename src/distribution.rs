@@ -0,0 +1,338 @@
+//! Discrete probability distributions over map keys.
+
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::error::Error;
+use std::fmt;
+use std::hash::Hash;
+
+use crate::num::ToF64;
+use crate::{ArithMap, Storage};
+
+/// Why a map could not be normalised into a [`Distribution`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum MassError<K> {
+    /// The value at `key` is negative.
+    Negative { key: K },
+    /// The value at `key` is infinite or NaN.
+    NotFinite { key: K },
+    /// The values sum to zero, or there are none.
+    Zero,
+    /// A mixing weight is not between zero and one.
+    Weight,
+}
+
+impl<K> fmt::Display for MassError<K>
+where
+    K: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MassError::Negative { key } => write!(f, "negative mass at key {:?}", key),
+            MassError::NotFinite { key } => write!(f, "non-finite mass at key {:?}", key),
+            MassError::Zero => write!(f, "total mass is zero"),
+            MassError::Weight => write!(f, "mixing weight outside zero to one"),
+        }
+    }
+}
+
+impl<K> Error for MassError<K> where K: fmt::Debug {}
+
+/// A map of non-negative probabilities summing to one.
+///
+/// Keys with zero probability are never stored, so the keys of the map are the support.
+///
+/// ```
+/// # use arith::*;
+/// let x = Distribution::new(arithmap!{"a" => 1.0, "b" => 3.0, "c" => 0.0}).unwrap();
+///
+/// assert_eq!(x.as_map(), &arithmap!{"a" => 0.25, "b" => 0.75});
+/// assert_eq!(x.get(&"c"), 0.0);
+/// ```
+///
+/// Counts of any primitive type work as well:
+///
+/// ```
+/// # use arith::*;
+/// let counts = ArithMap::<_, u64>::from_keys("abba".chars());
+/// let x = Distribution::new(counts).unwrap();
+///
+/// assert_eq!(x.get(&'a'), 0.5);
+/// ```
+pub struct Distribution<K, S = HashMap<K, f64>> {
+    map: ArithMap<K, f64, S>,
+}
+
+impl<K> Distribution<K>
+where
+    K: Eq + Hash + Clone,
+{
+    /// Divides every value by the total and drops zeros.
+    ///
+    /// ```
+    /// # use arith::*;
+    /// assert_eq!(Distribution::new(arithmap!{"a" => -1.0}).unwrap_err(), MassError::Negative { key: "a" });
+    /// assert_eq!(Distribution::new(arithmap!{"a" => 0}).unwrap_err(), MassError::Zero);
+    /// ```
+    pub fn new<V, T>(map: ArithMap<K, V, T>) -> Result<Self, MassError<K>>
+    where
+        T: Storage<K, V>,
+        V: ToF64,
+    {
+        Distribution::from_map(map)
+    }
+}
+
+impl<K, S> Distribution<K, S>
+where
+    S: Storage<K, f64>,
+    K: Clone,
+{
+    /// Like [`Distribution::new`], but into any storage.
+    ///
+    /// ```
+    /// # use arith::*;
+    /// # use std::collections::BTreeMap;
+    /// let x: Distribution<_, BTreeMap<_, _>> = Distribution::from_map(arithmap!{"a" => 1, "b" => 3}).unwrap();
+    ///
+    /// assert_eq!(x.as_map(), &arithbtreemap!{"a" => 0.25, "b" => 0.75});
+    /// ```
+    ///
+    /// Values whose sum overflows are scaled down first:
+    ///
+    /// ```
+    /// # use arith::*;
+    /// let x = Distribution::new(arithmap!{"a" => 1e308, "b" => 1e308}).unwrap();
+    ///
+    /// assert_eq!(x.into_map(), arithmap!{"a" => 0.5, "b" => 0.5});
+    /// ```
+    pub fn from_map<V, T>(map: ArithMap<K, V, T>) -> Result<Self, MassError<K>>
+    where
+        T: Storage<K, V>,
+        V: ToF64,
+    {
+        let mut mass = S::default();
+        let mut total = 0.0;
+        for (k, v) in map.storage {
+            let v = v.to_f64();
+            if !v.is_finite() {
+                return Err(MassError::NotFinite { key: k });
+            }
+            if v < 0.0 {
+                return Err(MassError::Negative { key: k });
+            }
+            total += v;
+            mass.insert(k, v);
+        }
+        if total == 0.0 {
+            return Err(MassError::Zero);
+        }
+        let mut map = ArithMap::from(mass);
+        if total.is_infinite() {
+            // Every value is finite but their sum isn't; scale them down before summing.
+            let max = map.storage.iter().map(|(_, &v)| v).fold(0.0, f64::max);
+            map /= max;
+            total = map.storage.iter().map(|(_, &v)| v).sum();
+        }
+        map /= total;
+        map.prune();
+        Ok(Distribution { map })
+    }
+
+    /// ```
+    /// # use arith::*;
+    /// let x = Distribution::new(arithmap!{"a" => 1.0}).unwrap();
+    ///
+    /// assert_eq!(x.as_map().len(), 1);
+    /// ```
+    pub fn as_map(&self) -> &ArithMap<K, f64, S> {
+        &self.map
+    }
+
+    /// ```
+    /// # use arith::*;
+    /// let x = Distribution::new(arithmap!{"a" => 2.0}).unwrap();
+    ///
+    /// assert_eq!(x.into_map(), arithmap!{"a" => 1.0});
+    /// ```
+    pub fn into_map(self) -> ArithMap<K, f64, S> {
+        self.map
+    }
+
+    /// Probability of `key`, zero outside the support.
+    ///
+    /// ```
+    /// # use arith::*;
+    /// let x = Distribution::new(arithmap!{"a" => 1.0, "b" => 1.0}).unwrap();
+    ///
+    /// assert_eq!(x.get(&"a"), 0.5);
+    /// assert_eq!(x.get(&"c"), 0.0);
+    /// ```
+    pub fn get(&self, key: &K) -> f64 {
+        self.map.storage.get(key).copied().unwrap_or(0.0)
+    }
+
+    /// Shannon entropy in nats.
+    ///
+    /// ```
+    /// # use arith::*;
+    /// let x = Distribution::new(arithmap!{"a" => 1.0, "b" => 1.0}).unwrap();
+    ///
+    /// assert!((x.entropy() - 2f64.ln()).abs() < 1e-12);
+    /// ```
+    pub fn entropy(&self) -> f64 {
+        -self.map.storage.iter().map(|(_, &p)| p * p.ln()).sum::<f64>()
+    }
+
+    /// Kullback-Leibler divergence of `other` from `self`, in nats; infinite if `other`
+    /// misses part of the support of `self`.
+    ///
+    /// ```
+    /// # use arith::*;
+    /// let x = Distribution::new(arithmap!{"a" => 1.0, "b" => 1.0}).unwrap();
+    /// let y = Distribution::new(arithmap!{"a" => 1.0}).unwrap();
+    ///
+    /// assert!((y.kl_divergence(&x) - 2f64.ln()).abs() < 1e-12);
+    /// assert_eq!(x.kl_divergence(&y), f64::INFINITY);
+    /// ```
+    pub fn kl_divergence(&self, other: &Self) -> f64 {
+        self.map
+            .storage
+            .iter()
+            .map(|(k, &p)| match other.map.storage.get(k) {
+                Some(&q) => p * (p / q).ln(),
+                None => f64::INFINITY,
+            })
+            .sum()
+    }
+
+    /// Jensen-Shannon divergence, in nats.
+    ///
+    /// ```
+    /// # use arith::*;
+    /// let x = Distribution::new(arithmap!{"a" => 1.0}).unwrap();
+    /// let y = Distribution::new(arithmap!{"b" => 1.0}).unwrap();
+    ///
+    /// assert!((x.js_divergence(&y) - 2f64.ln()).abs() < 1e-12);
+    /// assert_eq!(x.js_divergence(&x), 0.0);
+    /// ```
+    pub fn js_divergence(&self, other: &Self) -> f64
+    where
+        S: Clone,
+    {
+        // An even mix of two distributions always has positive mass.
+        let m = self.mix(other, 0.5).unwrap_or_else(|_| self.clone());
+        (self.kl_divergence(&m) + other.kl_divergence(&m)) / 2.0
+    }
+
+    /// ```
+    /// # use arith::*;
+    /// let x = Distribution::new(arithmap!{"a" => 1.0, "b" => 1.0}).unwrap();
+    /// let y = Distribution::new(arithmap!{"b" => 1.0, "c" => 1.0}).unwrap();
+    ///
+    /// assert_eq!(x.total_variation(&y), 0.5);
+    /// ```
+    pub fn total_variation(&self, other: &Self) -> f64 {
+        let left: f64 = self
+            .map
+            .storage
+            .iter()
+            .map(|(k, &p)| (p - other.get(k)).abs())
+            .sum();
+        let right: f64 = other
+            .map
+            .storage
+            .iter()
+            .filter(|(k, _)| self.map.storage.get(k).is_none())
+            .map(|(_, &q)| q)
+            .sum();
+        (left + right) / 2.0
+    }
+
+    /// `(1 - weight) * self + weight * other`; an error unless `weight` is between zero and
+    /// one.
+    ///
+    /// ```
+    /// # use arith::*;
+    /// let x = Distribution::new(arithmap!{"a" => 1.0}).unwrap();
+    /// let y = Distribution::new(arithmap!{"b" => 1.0}).unwrap();
+    ///
+    /// assert_eq!(x.mix(&y, 0.25).unwrap().into_map(), arithmap!{"a" => 0.75, "b" => 0.25});
+    /// assert_eq!(x.mix(&y, 2.0).unwrap_err(), MassError::Weight);
+    /// assert_eq!(x.mix(&y, -0.5).unwrap_err(), MassError::Weight);
+    /// ```
+    pub fn mix(&self, other: &Self, weight: f64) -> Result<Self, MassError<K>>
+    where
+        S: Clone,
+    {
+        if !(0.0..=1.0).contains(&weight) {
+            return Err(MassError::Weight);
+        }
+        Distribution::from_map(self.map.clone() * (1.0 - weight) + other.map.clone() * weight)
+    }
+
+    /// Restricts the distribution to the keys `f` accepts and renormalises; an error if they
+    /// have no mass.
+    ///
+    /// ```
+    /// # use arith::*;
+    /// let x = Distribution::new(arithmap!{"a" => 1.0, "b" => 1.0, "c" => 2.0}).unwrap();
+    ///
+    /// assert_eq!(x.condition(|k| *k != "c").unwrap().into_map(), arithmap!{"a" => 0.5, "b" => 0.5});
+    /// assert_eq!(x.condition(|k| *k == "d").unwrap_err(), MassError::Zero);
+    /// ```
+    pub fn condition<F>(&self, mut f: F) -> Result<Self, MassError<K>>
+    where
+        S: Clone,
+        F: FnMut(&K) -> bool,
+    {
+        let mut map = self.map.clone();
+        map.storage.retain(|k, _| f(k));
+        Distribution::from_map(map)
+    }
+}
+
+/// ```
+/// # use arith::*;
+/// # use std::convert::TryFrom;
+/// let x = Distribution::try_from(arithmap!{"a" => 1.0}).unwrap();
+///
+/// assert_eq!(x.get(&"a"), 1.0);
+/// ```
+impl<K, S> TryFrom<ArithMap<K, f64, S>> for Distribution<K, S>
+where
+    S: Storage<K, f64>,
+    K: Clone,
+{
+    type Error = MassError<K>;
+    fn try_from(map: ArithMap<K, f64, S>) -> Result<Self, Self::Error> {
+        Distribution::from_map(map)
+    }
+}
+
+impl<K, S> Clone for Distribution<K, S>
+where
+    S: Clone,
+{
+    fn clone(&self) -> Self {
+        Distribution { map: self.map.clone() }
+    }
+}
+
+impl<K, S> PartialEq for Distribution<K, S>
+where
+    S: PartialEq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.map == other.map
+    }
+}
+
+impl<K, S> fmt::Debug for Distribution<K, S>
+where
+    S: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.map.fmt(f)
+    }
+}
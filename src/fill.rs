@@ -0,0 +1,101 @@
+//! Map-wise operations with explicit values for missing keys.
+
+use std::ops::{AddAssign, DivAssign, MulAssign, RemAssign, SubAssign};
+
+use crate::{ArithMap, Join, Storage};
+
+impl<K, V, S> ArithMap<K, V, S>
+where
+    S: Storage<K, V>,
+    V: Clone,
+{
+    /// ```
+    /// # use arith::*;
+    /// let x = || arithmap!{"a" => 1, "b" => 2};
+    /// let y = || arithmap!{"b" => 3, "c" => 4};
+    ///
+    /// assert_eq!(x().add_fill(y(), Join::Right(10)), arithmap!{"b" => 5, "c" => 14});
+    /// assert_eq!(x().add_fill(y(), Join::outer(10)), arithmap!{"a" => 11, "b" => 5, "c" => 14});
+    /// ```
+    pub fn add_fill(self, other: Self, join: Join<V>) -> Self
+    where
+        V: AddAssign,
+    {
+        self.combine(other, join, |mut v1, v2| {
+            v1 += v2;
+            v1
+        })
+    }
+
+    /// Subtracting from a baseline for keys the left-hand map lacks:
+    ///
+    /// ```
+    /// # use arith::*;
+    /// let used = arithmap!{"a" => 30};
+    /// let spent = arithmap!{"a" => 10, "b" => 20};
+    ///
+    /// assert_eq!(used.sub_fill(spent, Join::Right(100)), arithmap!{"a" => 20, "b" => 80});
+    /// ```
+    pub fn sub_fill(self, other: Self, join: Join<V>) -> Self
+    where
+        V: SubAssign,
+    {
+        self.combine(other, join, |mut v1, v2| {
+            v1 -= v2;
+            v1
+        })
+    }
+
+    /// Multiplying by one where a key is missing:
+    ///
+    /// ```
+    /// # use arith::*;
+    /// let x = arithmap!{"a" => 2, "b" => 3};
+    /// let y = arithmap!{"b" => 4, "c" => 5};
+    ///
+    /// assert_eq!(x.mul_fill(y, Join::outer(1)), arithmap!{"a" => 2, "b" => 12, "c" => 5});
+    /// ```
+    pub fn mul_fill(self, other: Self, join: Join<V>) -> Self
+    where
+        V: MulAssign,
+    {
+        self.combine(other, join, |mut v1, v2| {
+            v1 *= v2;
+            v1
+        })
+    }
+
+    /// ```
+    /// # use arith::*;
+    /// let x = arithmap!{"a" => 6, "b" => 8};
+    /// let y = arithmap!{"b" => 4};
+    ///
+    /// assert_eq!(x.div_fill(y, Join::Left(2)), arithmap!{"a" => 3, "b" => 2});
+    /// ```
+    pub fn div_fill(self, other: Self, join: Join<V>) -> Self
+    where
+        V: DivAssign,
+    {
+        self.combine(other, join, |mut v1, v2| {
+            v1 /= v2;
+            v1
+        })
+    }
+
+    /// ```
+    /// # use arith::*;
+    /// let x = arithmap!{"a" => 7, "b" => 8};
+    /// let y = arithmap!{"b" => 3};
+    ///
+    /// assert_eq!(x.rem_fill(y, Join::Left(4)), arithmap!{"a" => 3, "b" => 2});
+    /// ```
+    pub fn rem_fill(self, other: Self, join: Join<V>) -> Self
+    where
+        V: RemAssign,
+    {
+        self.combine(other, join, |mut v1, v2| {
+            v1 %= v2;
+            v1
+        })
+    }
+}
@@ -0,0 +1,205 @@
+//! Interned string keys, hashed as small integers.
+
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::hash::{BuildHasherDefault, Hasher};
+use std::ops::AddAssign;
+
+use crate::{ArithMap, Storage};
+
+/// A string interned in an [`Interner`].
+///
+/// Symbols are only meaningful to the interner that issued them; maps that share an
+/// interner combine by comparing integers.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Symbol(u32);
+
+/// Hasher that hashes a [`Symbol`] with a single multiplication, as FxHash does, so that
+/// the high bits `HashMap` probes with vary too.
+///
+/// ```
+/// # use arith::*;
+/// # use std::hash::Hasher;
+/// let mut hasher = SymbolHasher::default();
+/// hasher.write_u32(1);
+///
+/// assert_ne!(hasher.finish() >> 57, 0);
+/// ```
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SymbolHasher(u64);
+
+impl Hasher for SymbolHasher {
+    fn finish(&self) -> u64 {
+        self.0.wrapping_mul(0x517c_c1b7_2722_0a95)
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for &b in bytes {
+            self.0 = (self.0 << 8) | u64::from(b);
+        }
+    }
+
+    fn write_u32(&mut self, i: u32) {
+        self.0 = u64::from(i);
+    }
+}
+
+pub type BuildSymbolHasher = BuildHasherDefault<SymbolHasher>;
+
+/// [`ArithMap`] keyed by [`Symbol`]s, with multiplicative hashing.
+pub type SymbolMap<V> = ArithMap<Symbol, V, HashMap<Symbol, V, BuildSymbolHasher>>;
+
+/// Table of interned strings.
+///
+/// ```
+/// # use arith::*;
+/// let mut words = Interner::new();
+/// let x = words.intern_map(vec![("a", 1), ("b", 2)]);
+/// let y = words.intern_map(vec![("b", 2), ("c", 3)]);
+/// let z = x + y;
+///
+/// assert_eq!(z.get_str(&words, "b"), Some(&4));
+/// assert_eq!(words.resolve_map(z), arithmap!{"a" => 1, "b" => 4, "c" => 3});
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct Interner {
+    ids: HashMap<String, Symbol>,
+    names: Vec<String>,
+}
+
+impl Interner {
+    /// ```
+    /// # use arith::*;
+    /// let words = Interner::new();
+    ///
+    /// assert!(words.is_empty());
+    /// ```
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Returns the symbol for `name`, adding it if it is new; panics past `u32::MAX` names.
+    ///
+    /// ```
+    /// # use arith::*;
+    /// let mut words = Interner::new();
+    /// let a = words.intern("a");
+    ///
+    /// assert_eq!(words.intern("a"), a);
+    /// assert_ne!(words.intern("b"), a);
+    /// ```
+    pub fn intern(&mut self, name: &str) -> Symbol {
+        if let Some(&symbol) = self.ids.get(name) {
+            return symbol;
+        }
+        let symbol = Symbol(u32::try_from(self.names.len()).expect("too many symbols"));
+        self.ids.insert(name.to_owned(), symbol);
+        self.names.push(name.to_owned());
+        symbol
+    }
+
+    /// Returns the symbol for `name` without adding it.
+    ///
+    /// ```
+    /// # use arith::*;
+    /// let mut words = Interner::new();
+    /// let a = words.intern("a");
+    ///
+    /// assert_eq!(words.get("a"), Some(a));
+    /// assert_eq!(words.get("b"), None);
+    /// ```
+    pub fn get(&self, name: &str) -> Option<Symbol> {
+        self.ids.get(name).copied()
+    }
+
+    /// ```
+    /// # use arith::*;
+    /// let mut words = Interner::new();
+    /// let a = words.intern("a");
+    ///
+    /// assert_eq!(words.resolve(a), Some("a"));
+    /// ```
+    pub fn resolve(&self, symbol: Symbol) -> Option<&str> {
+        self.names.get(symbol.0 as usize).map(String::as_str)
+    }
+
+    /// ```
+    /// # use arith::*;
+    /// let mut words = Interner::new();
+    /// words.intern("a");
+    /// words.intern("a");
+    ///
+    /// assert_eq!(words.len(), 1);
+    /// ```
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    /// ```
+    /// # use arith::*;
+    /// assert!(Interner::new().is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    /// Builds a map from string pairs, interning every key; values under repeated keys are
+    /// summed.
+    ///
+    /// ```
+    /// # use arith::*;
+    /// let mut words = Interner::new();
+    /// let x = words.intern_map(vec![("a", 1), ("a", 2)]);
+    ///
+    /// assert_eq!(x.storage[&words.intern("a")], 3);
+    /// ```
+    pub fn intern_map<'a, V, I>(&mut self, pairs: I) -> SymbolMap<V>
+    where
+        I: IntoIterator<Item = (&'a str, V)>,
+        V: AddAssign,
+    {
+        pairs.into_iter().map(|(k, v)| (self.intern(k), v)).collect()
+    }
+
+    /// Turns symbol keys back into strings; symbols not issued by this interner are left
+    /// out.
+    ///
+    /// ```
+    /// # use arith::*;
+    /// let mut words = Interner::new();
+    /// let x = words.intern_map(vec![("a", 1)]);
+    ///
+    /// assert_eq!(words.resolve_map(x), arithmap!{"a" => 1});
+    /// ```
+    pub fn resolve_map<V, S>(&self, map: ArithMap<Symbol, V, S>) -> ArithMap<&str, V>
+    where
+        S: Storage<Symbol, V>,
+    {
+        let mut storage = HashMap::with_capacity(map.len());
+        for (k, v) in map {
+            if let Some(name) = self.resolve(k) {
+                storage.insert(name, v);
+            }
+        }
+        ArithMap::from(storage)
+    }
+}
+
+impl<V, S> ArithMap<Symbol, V, S>
+where
+    S: Storage<Symbol, V>,
+{
+    /// Looks a key up by its string.
+    ///
+    /// ```
+    /// # use arith::*;
+    /// let mut words = Interner::new();
+    /// let x = words.intern_map(vec![("a", 1)]);
+    ///
+    /// assert_eq!(x.get_str(&words, "a"), Some(&1));
+    /// assert_eq!(x.get_str(&words, "b"), None);
+    /// ```
+    pub fn get_str(&self, interner: &Interner, key: &str) -> Option<&V> {
+        interner.get(key).and_then(|k| self.storage.get(&k))
+    }
+}
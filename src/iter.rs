@@ -0,0 +1,210 @@
+//! Building maps from iterators, iterating over them, and summing iterators of maps.
+
+use std::iter::{FromIterator, Product, Sum};
+use std::ops::{AddAssign, MulAssign};
+
+use crate::{ArithMap, Storage};
+
+/// Values under repeated keys are summed, so counting is a `collect`.
+///
+/// ```
+/// # use arith::*;
+/// let x: ArithMap<_, _> = "abracadabra".chars().map(|c| (c, 1)).collect();
+///
+/// assert_eq!(x, arithmap!{'a' => 5, 'b' => 2, 'r' => 2, 'c' => 1, 'd' => 1});
+/// ```
+impl<K, V, S> FromIterator<(K, V)> for ArithMap<K, V, S>
+where
+    S: Storage<K, V>,
+    V: AddAssign,
+{
+    fn from_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = (K, V)>,
+    {
+        let mut map = ArithMap::new();
+        map.extend(iter);
+        map
+    }
+}
+
+/// Values under keys already in the map are added to, not replaced.
+///
+/// ```
+/// # use arith::*;
+/// let mut x = arithmap!{"a" => 1, "b" => 2};
+/// x.extend(vec![("b", 3), ("c", 4), ("c", 5)]);
+///
+/// assert_eq!(x, arithmap!{"a" => 1, "b" => 5, "c" => 9});
+/// ```
+impl<K, V, S> Extend<(K, V)> for ArithMap<K, V, S>
+where
+    S: Storage<K, V>,
+    V: AddAssign,
+{
+    fn extend<I>(&mut self, iter: I)
+    where
+        I: IntoIterator<Item = (K, V)>,
+    {
+        for (k, v2) in iter {
+            if let Some(v1) = self.storage.get_mut(&k) {
+                *v1 += v2;
+            } else {
+                self.storage.insert(k, v2);
+            }
+        }
+        self.storage.tidy();
+    }
+}
+
+/// ```
+/// # use arith::*;
+/// let x = arithbtreemap!{"a" => 1, "b" => 2};
+/// let y: Vec<_> = x.into_iter().collect();
+///
+/// assert_eq!(y, vec![("a", 1), ("b", 2)]);
+/// ```
+impl<K, V, S> IntoIterator for ArithMap<K, V, S>
+where
+    S: Storage<K, V>,
+{
+    type Item = (K, V);
+    type IntoIter = S::IntoIter;
+    fn into_iter(self) -> Self::IntoIter {
+        self.storage.into_iter()
+    }
+}
+
+/// ```
+/// # use arith::*;
+/// let x = arithbtreemap!{"a" => 1, "b" => 2};
+/// let y: Vec<_> = (&x).into_iter().collect();
+///
+/// assert_eq!(y, vec![(&"a", &1), (&"b", &2)]);
+/// ```
+impl<'a, K, V, S> IntoIterator for &'a ArithMap<K, V, S>
+where
+    S: Storage<K, V>,
+{
+    type Item = (&'a K, &'a V);
+    type IntoIter = S::Iter<'a>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.storage.iter()
+    }
+}
+
+/// ```
+/// # use arith::*;
+/// let mut x = arithmap!{"a" => 1, "b" => 2};
+/// for (_, v) in &mut x {
+///     *v *= 10;
+/// }
+///
+/// assert_eq!(x, arithmap!{"a" => 10, "b" => 20});
+/// ```
+impl<'a, K, V, S> IntoIterator for &'a mut ArithMap<K, V, S>
+where
+    S: Storage<K, V>,
+{
+    type Item = (&'a K, &'a mut V);
+    type IntoIter = S::IterMut<'a>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.storage.iter_mut()
+    }
+}
+
+/// The sum of no maps is the empty map.
+///
+/// ```
+/// # use arith::*;
+/// let x = vec![arithmap!{"a" => 1}, arithmap!{"a" => 2, "b" => 3}, arithmap!{"c" => 4}];
+///
+/// assert_eq!(x.into_iter().sum::<ArithMap<_, _>>(), arithmap!{"a" => 3, "b" => 3, "c" => 4});
+/// ```
+impl<K, V, S> Sum for ArithMap<K, V, S>
+where
+    S: Storage<K, V>,
+    V: AddAssign,
+{
+    fn sum<I>(iter: I) -> Self
+    where
+        I: Iterator<Item = Self>,
+    {
+        iter.fold(ArithMap::new(), |mut sum, map| {
+            sum += map;
+            sum
+        })
+    }
+}
+
+/// ```
+/// # use arith::*;
+/// let x = vec![arithmap!{"a" => 1}, arithmap!{"a" => 2, "b" => 3}];
+///
+/// assert_eq!(x.iter().sum::<ArithMap<_, _>>(), arithmap!{"a" => 3, "b" => 3});
+/// ```
+impl<'a, K, V, S> Sum<&'a ArithMap<K, V, S>> for ArithMap<K, V, S>
+where
+    S: Storage<K, V> + 'a,
+    K: Clone + 'a,
+    V: for<'x> AddAssign<&'x V> + Clone + 'a,
+{
+    fn sum<I>(iter: I) -> Self
+    where
+        I: Iterator<Item = &'a Self>,
+    {
+        iter.fold(ArithMap::new(), |mut sum, map| {
+            sum += map;
+            sum
+        })
+    }
+}
+
+/// Keeps the keys present in every map, like `*`; the product of no maps is the empty map.
+///
+/// ```
+/// # use arith::*;
+/// let x = vec![arithmap!{"a" => 2, "b" => 3}, arithmap!{"a" => 4, "b" => 5}, arithmap!{"a" => 6}];
+///
+/// assert_eq!(x.into_iter().product::<ArithMap<_, _>>(), arithmap!{"a" => 48});
+/// ```
+impl<K, V, S> Product for ArithMap<K, V, S>
+where
+    S: Storage<K, V>,
+    V: for<'x> MulAssign<&'x V>,
+{
+    fn product<I>(iter: I) -> Self
+    where
+        I: Iterator<Item = Self>,
+    {
+        iter.reduce(|mut product, map| {
+            product *= map;
+            product
+        })
+        .unwrap_or_default()
+    }
+}
+
+/// ```
+/// # use arith::*;
+/// let x = vec![arithmap!{"a" => 2, "b" => 3}, arithmap!{"a" => 4}];
+///
+/// assert_eq!(x.iter().product::<ArithMap<_, _>>(), arithmap!{"a" => 8});
+/// ```
+impl<'a, K, V, S> Product<&'a ArithMap<K, V, S>> for ArithMap<K, V, S>
+where
+    S: Storage<K, V> + Clone + 'a,
+    K: 'a,
+    V: for<'x> MulAssign<&'x V> + 'a,
+{
+    fn product<I>(mut iter: I) -> Self
+    where
+        I: Iterator<Item = &'a Self>,
+    {
+        let first = iter.next().cloned().unwrap_or_default();
+        iter.fold(first, |mut product, map| {
+            product *= map;
+            product
+        })
+    }
+}
@@ -1,23 +1,344 @@
 //! Containers with arithmetic operations support.
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fmt;
-use std::ops::{Add, AddAssign, Sub, SubAssign, Mul, MulAssign};
+use std::marker::PhantomData;
+use std::ops::{Add, AddAssign, Sub, SubAssign, Mul, MulAssign, Div, DivAssign, Rem, RemAssign, Neg};
 
+mod counter;
+mod dense;
+mod distribution;
+mod fill;
+mod intern;
+mod iter;
+mod matrix;
+mod num;
+mod overflow;
+#[cfg(feature = "rayon")]
+mod par;
+mod poly;
+mod prune;
+#[cfg(feature = "serde")]
+mod serde_impl;
+mod sparse;
+mod stats;
+mod storage;
+mod tensor;
+mod transform;
+mod vector;
 
-#[derive(Eq, PartialEq, Default)]
-pub struct ArithMap<'a, V> {
-    pub hashmap: HashMap<&'a str, V>,
+pub use dense::ArithVec;
+pub use distribution::{Distribution, MassError};
+pub use intern::{BuildSymbolHasher, Interner, Symbol, SymbolHasher, SymbolMap};
+pub use matrix::ArithMatrix;
+pub use num::{Float, Integer, One, ToF64, Zero};
+pub use overflow::Overflow;
+pub use poly::{Monomial, Polynomial};
+pub use prune::{Epsilon, Exact, PrunePolicy, Pruned, PrunedMap};
+pub use sparse::{Sparse, SparseMap};
+pub use storage::Storage;
+
+
+/// ```
+/// # use arith::*;
+/// let x = arithmap!{String::from("a") => 1, String::from("b") => 2};
+/// let y = arithmap!{(0, 'a') => 1.0, (1, 'b') => 2.0};
+///
+/// assert_eq!(x.storage["a"], 1);
+/// assert_eq!(y.storage[&(1, 'b')], 2.0);
+/// ```
+///
+/// Values only need to be `Clone`, so arbitrary-precision numbers work too:
+///
+/// ```
+/// # use arith::*;
+/// # use num_bigint::BigInt;
+/// let x = arithmap!{"a" => BigInt::from(10).pow(30)};
+/// let y = arithmap!{"a" => BigInt::from(1), "b" => BigInt::from(2)};
+///
+/// assert_eq!((&x + &y) * BigInt::from(2), arithmap!{
+///     "a" => BigInt::from(2) * BigInt::from(10).pow(30) + 2,
+///     "b" => BigInt::from(4),
+/// });
+/// ```
+///
+/// Operations that need a zero, like map-wise `-` or [`ArithMap::prune`], take it from
+/// `num_traits::Zero` with the `num-traits` feature:
+///
+/// ```
+/// # use arith::*;
+/// # use num_bigint::BigInt;
+/// # #[cfg(feature = "num-traits")]
+/// # {
+/// let x = arithmap!{"a" => BigInt::from(10).pow(30), "b" => BigInt::from(2)};
+/// let y = arithmap!{"b" => BigInt::from(2), "c" => BigInt::from(1)};
+/// let mut z = x - y;
+/// z.prune();
+///
+/// assert_eq!(z, arithmap!{"a" => BigInt::from(10).pow(30), "c" => BigInt::from(-1)});
+/// # }
+/// ```
+///
+/// Every operator is also implemented for references, cloning only what the result needs:
+///
+/// ```
+/// # use arith::*;
+/// let x = arithmap!{"a" => 1, "b" => 2};
+/// let y = arithmap!{"b" => 2, "c" => 3};
+///
+/// assert_eq!(&x + &y, arithmap!{"a" => 1, "b" => 4, "c" => 3});
+/// assert_eq!(&x - y.clone(), arithmap!{"a" => 1, "b" => 0, "c" => -3});
+/// assert_eq!(x.clone() * &y, arithmap!{"b" => 4});
+/// assert_eq!(&x * 2, arithmap!{"a" => 2, "b" => 4});
+/// assert_eq!(-&x, arithmap!{"a" => -1, "b" => -2});
+/// ```
+pub struct ArithMap<K, V, S = HashMap<K, V>> {
+    pub storage: S,
+    _marker: PhantomData<(K, V)>,
+}
+
+/// ```
+/// # use arith::*;
+/// let x = arithbtreemap!{"c" => 3, "a" => 1, "b" => 2};
+/// let y: Vec<_> = x.storage.range("b"..).collect();
+///
+/// assert_eq!(format!("{:?}", x), r#"{"a": 1, "b": 2, "c": 3}"#);
+/// assert_eq!(y, vec![(&"b", &2), (&"c", &3)]);
+///
+/// let z = x - arithbtreemap!{"d" => 4} * 2;
+/// assert_eq!(format!("{:?}", z), r#"{"a": 1, "b": 2, "c": 3, "d": -8}"#);
+/// ```
+pub type ArithBTreeMap<K, V> = ArithMap<K, V, BTreeMap<K, V>>;
+
+/// Which keys a map-wise operation keeps, and the value standing in for a missing one.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Join<V> {
+    /// Keys present in both maps.
+    Inner,
+    /// Keys of the left-hand map, with missing right-hand values filled in.
+    Left(V),
+    /// Keys of the right-hand map, with missing left-hand values filled in.
+    Right(V),
+    /// Keys of either map, with missing values filled in on each side.
+    Outer { left: V, right: V },
+}
+
+impl<V> Join<V> {
+    /// Keys of either map, with the same value filled in on both sides.
+    ///
+    /// ```
+    /// # use arith::*;
+    /// assert_eq!(Join::outer(0), Join::Outer { left: 0, right: 0 });
+    /// ```
+    pub fn outer(fill: V) -> Self
+    where
+        V: Clone,
+    {
+        Join::Outer { left: fill.clone(), right: fill }
+    }
+
+    /// The values standing in for a missing left-hand and right-hand value, `None` where
+    /// the key is dropped instead.
+    fn fills(self) -> (Option<V>, Option<V>) {
+        match self {
+            Join::Inner => (None, None),
+            Join::Left(right) => (None, Some(right)),
+            Join::Right(left) => (Some(left), None),
+            Join::Outer { left, right } => (Some(left), Some(right)),
+        }
+    }
+}
+
+/// What map-wise division does with keys that are missing or zero on the right-hand side.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ZeroDivisor<V> {
+    /// Divide by zero anyway.
+    Divide,
+    /// Leave the left-hand value unchanged.
+    Keep,
+    /// Remove the key from the result, as the `/` and `%` operators do.
+    Remove,
+    /// Replace the left-hand value with the given one.
+    Fill(V),
+}
+
+impl<V> ZeroDivisor<V>
+where
+    V: Zero + Clone,
+{
+    fn apply<F>(&self, v1: &mut V, mut op: F) -> bool
+    where
+        F: FnMut(&mut V, &V),
+    {
+        match self {
+            ZeroDivisor::Divide => op(v1, &V::zero()),
+            ZeroDivisor::Keep => (),
+            ZeroDivisor::Remove => return false,
+            ZeroDivisor::Fill(v) => *v1 = v.clone(),
+        }
+        true
+    }
+}
+
+impl<K, V, S> ArithMap<K, V, S>
+where
+    S: Storage<K, V>,
+{
+    /// ```
+    /// # use arith::*;
+    /// let x: ArithMap<&str, i32> = ArithMap::new();
+    ///
+    /// assert!(x.storage.is_empty());
+    /// ```
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// ```
+    /// # use arith::*;
+    /// let x = arithmap!{"a" => 1};
+    ///
+    /// assert_eq!(x.into_storage()["a"], 1);
+    /// ```
+    pub fn into_storage(self) -> S {
+        self.storage
+    }
+
+    /// ```
+    /// # use arith::*;
+    /// let x = arithmap!{"a" => 1, "b" => 0};
+    ///
+    /// assert_eq!(x.len(), 2);
+    /// ```
+    pub fn len(&self) -> usize {
+        self.storage.len()
+    }
+
+    /// ```
+    /// # use arith::*;
+    /// let x: ArithMap<&str, i32> = arithmap!{};
+    ///
+    /// assert!(x.is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.storage.is_empty()
+    }
+}
+
+/// Accessors for code written against the former `.hashmap` field.
+impl<K, V, H> ArithMap<K, V, HashMap<K, V, H>> {
+    /// ```
+    /// # use arith::*;
+    /// let x = arithmap!{"a" => 1};
+    ///
+    /// #[allow(deprecated)]
+    /// let y = x.hashmap();
+    /// assert_eq!(y["a"], 1);
+    /// ```
+    #[deprecated(since = "0.3.0", note = "use the `storage` field")]
+    pub fn hashmap(&self) -> &HashMap<K, V, H> {
+        &self.storage
+    }
+
+    /// ```
+    /// # use arith::*;
+    /// let mut x = arithmap!{"a" => 1};
+    ///
+    /// #[allow(deprecated)]
+    /// x.hashmap_mut().insert("b", 2);
+    /// assert_eq!(x, arithmap!{"a" => 1, "b" => 2});
+    /// ```
+    #[deprecated(since = "0.3.0", note = "use the `storage` field")]
+    pub fn hashmap_mut(&mut self) -> &mut HashMap<K, V, H> {
+        &mut self.storage
+    }
+}
+
+/// Wrapping storage tidies it, so adapters like [`Pruned`] hold up their invariant from the
+/// start.
+///
+/// ```
+/// # use arith::*;
+/// # use std::collections::HashMap;
+/// let x: PrunedMap<_, _> = ArithMap::from(Pruned::from(HashMap::from([("a", 0), ("b", 1)])));
+///
+/// assert_eq!(x.len(), 1);
+/// ```
+impl<K, V, S> From<S> for ArithMap<K, V, S>
+where
+    S: Storage<K, V>,
+{
+    fn from(mut storage: S) -> Self {
+        storage.tidy();
+        ArithMap { storage, _marker: PhantomData }
+    }
+}
+
+impl<K, V, S> Default for ArithMap<K, V, S>
+where
+    S: Default,
+{
+    fn default() -> Self {
+        ArithMap { storage: Default::default(), _marker: PhantomData }
+    }
+}
+
+impl<K, V, S> Clone for ArithMap<K, V, S>
+where
+    S: Clone,
+{
+    fn clone(&self) -> Self {
+        ArithMap { storage: self.storage.clone(), _marker: PhantomData }
+    }
+}
+
+/// The empty map, so that maps can be values of other maps.
+///
+/// ```
+/// # use arith::*;
+/// let x = arithmap!{"a" => arithmap!{"x" => 1}, "b" => arithmap!{"y" => 2}};
+/// let y = arithmap!{"a" => arithmap!{"x" => -1, "z" => 3}};
+/// let mut z = x + y;
+/// z.prune();
+///
+/// assert_eq!(z, arithmap!{"a" => arithmap!{"x" => 0, "z" => 3}, "b" => arithmap!{"y" => 2}});
+/// assert!(ArithMap::<&str, ArithMap<&str, i32>>::zero().is_zero());
+/// ```
+impl<K, V, S> Zero for ArithMap<K, V, S>
+where
+    S: Storage<K, V>,
+    V: Zero,
+{
+    fn zero() -> Self {
+        Default::default()
+    }
+
+    fn is_zero(&self) -> bool {
+        self.storage.iter().all(|(_, v)| v.is_zero())
+    }
 }
 
-impl<V> fmt::Debug for ArithMap<'_, V>
+impl<K, V, S> PartialEq for ArithMap<K, V, S>
 where
-    V: fmt::Debug,
+    S: PartialEq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.storage == other.storage
+    }
+}
+
+impl<K, V, S> Eq for ArithMap<K, V, S>
+where
+    S: Eq,
+{
+}
+
+impl<K, V, S> fmt::Debug for ArithMap<K, V, S>
+where
+    S: fmt::Debug,
 {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        f.debug_map()
-            .entries(self.hashmap.iter().map(|(k, v)| (k, v)))
-            .finish()
+        self.storage.fmt(f)
     }
 }
 
@@ -25,9 +346,9 @@ where
 /// ```
 /// # use arith::*;
 /// let map = arithmap!{"a" => 1, "b" => 2};
-/// assert_eq!(map.hashmap["a"], 1);
-/// assert_eq!(map.hashmap["b"], 2);
-/// assert_eq!(map.hashmap.get("c"), None);
+/// assert_eq!(map.storage["a"], 1);
+/// assert_eq!(map.storage["b"], 2);
+/// assert_eq!(map.storage.get("c"), None);
 /// ```
 macro_rules! arithmap {
     (@single $($x:tt)*) => (());
@@ -40,14 +361,80 @@ macro_rules! arithmap {
             $(
                 let _ = _map.insert($key, $value);
             )*
-            ArithMap{hashmap: _map}
+            $crate::ArithMap::from(_map)
         }
     };
 }
 
-impl<V> ArithMap<'_, V>
+#[macro_export(local_inner_macros)]
+/// ```
+/// # use arith::*;
+/// let map = arithbtreemap!{"a" => 1, "b" => 2};
+/// assert_eq!(map.storage["a"], 1);
+/// assert_eq!(map.storage["b"], 2);
+/// assert_eq!(map.storage.get("c"), None);
+/// ```
+macro_rules! arithbtreemap {
+    ($($key:expr => $value:expr,)+) => { arithbtreemap!($($key => $value),+) };
+    ($($key:expr => $value:expr),*) => {
+        {
+            let mut _map = ::std::collections::BTreeMap::new();
+            $(
+                let _ = _map.insert($key, $value);
+            )*
+            $crate::ArithMap::from(_map)
+        }
+    };
+}
+
+impl<K, V, S> ArithMap<K, V, S>
 where
-    V: Copy + Default + PartialEq,
+    S: Storage<K, V>,
+    V: Clone,
+{
+    /// ```
+    /// # use arith::*;
+    /// # use std::ops::{Add, Mul};
+    /// let x = || arithmap!{"a" => 1, "b" => 2};
+    /// let y = || arithmap!{"b" => 3, "c" => 4};
+    ///
+    /// assert_eq!(x().combine(y(), Join::Inner, Mul::mul), arithmap!{"b" => 6});
+    /// assert_eq!(x().combine(y(), Join::Left(1), Mul::mul), arithmap!{"a" => 1, "b" => 6});
+    /// assert_eq!(x().combine(y(), Join::Right(1), Mul::mul), arithmap!{"b" => 6, "c" => 4});
+    /// assert_eq!(
+    ///     x().combine(y(), Join::Outer { left: 10, right: 0 }, Add::add),
+    ///     arithmap!{"a" => 1, "b" => 5, "c" => 14},
+    /// );
+    /// ```
+    pub fn combine<F>(self, other: Self, join: Join<V>, mut f: F) -> Self
+    where
+        F: FnMut(V, V) -> V,
+    {
+        let (left, right) = join.fills();
+        let mut storage = S::default();
+        for (k, v1) in self.storage {
+            match (other.storage.get(&k), &right) {
+                (Some(v2), _) => storage.insert(k, f(v1, v2.clone())),
+                (None, Some(fill)) => storage.insert(k, f(v1, fill.clone())),
+                (None, None) => None,
+            };
+        }
+        if let Some(fill) = left {
+            for (k, v2) in other.storage {
+                // Keys of both maps are already in `storage`; those of `other` alone are not.
+                if storage.get(&k).is_none() {
+                    storage.insert(k, f(fill.clone(), v2));
+                }
+            }
+        }
+        ArithMap::from(storage)
+    }
+}
+
+impl<K, V, S> ArithMap<K, V, S>
+where
+    S: Storage<K, V>,
+    V: Zero,
 {
     /// ```
     /// # use arith::*;
@@ -58,8 +445,54 @@ where
     /// assert_eq!(x, y);
     /// ```
     pub fn prune(&mut self) {
-        let zero: V = Default::default();
-        self.hashmap.retain(|_, &mut v| v != zero);
+        self.storage.retain(|_, v| !v.is_zero());
+    }
+
+    /// ```
+    /// # use arith::*;
+    /// let x = arithmap!{"a" => 6, "b" => 4, "c" => 2};
+    /// let y = arithmap!{"a" => 3, "b" => 0};
+    ///
+    /// assert_eq!(x.div_or(y, ZeroDivisor::Keep), arithmap!{"a" => 2, "b" => 4, "c" => 2});
+    /// ```
+    pub fn div_or(mut self, other: Self, on_zero: ZeroDivisor<V>) -> Self
+    where
+        V: for<'x> DivAssign<&'x V> + Clone,
+    {
+        self.divide(&other, |v1, v2| *v1 /= v2, |v1| on_zero.apply(v1, |v1, v2| *v1 /= v2));
+        self
+    }
+
+    /// ```
+    /// # use arith::*;
+    /// let x = arithmap!{"a" => 7, "b" => 4, "c" => 2};
+    /// let y = arithmap!{"a" => 3, "b" => 0};
+    ///
+    /// assert_eq!(x.rem_or(y, ZeroDivisor::Fill(0)), arithmap!{"a" => 1, "b" => 0, "c" => 0});
+    /// ```
+    pub fn rem_or(mut self, other: Self, on_zero: ZeroDivisor<V>) -> Self
+    where
+        V: for<'x> RemAssign<&'x V> + Clone,
+    {
+        self.divide(&other, |v1, v2| *v1 %= v2, |v1| on_zero.apply(v1, |v1, v2| *v1 %= v2));
+        self
+    }
+
+    /// Applies `op` to keys with a non-zero divisor and `on_zero` to the rest, dropping the
+    /// key if `on_zero` returns `false`.
+    fn divide<F, G>(&mut self, other: &Self, mut op: F, mut on_zero: G)
+    where
+        F: FnMut(&mut V, &V),
+        G: FnMut(&mut V) -> bool,
+    {
+        self.storage.retain(|k, v1| match other.storage.get(k) {
+            Some(v2) if !v2.is_zero() => {
+                op(v1, v2);
+                true
+            }
+            _ => on_zero(v1),
+        });
+        self.storage.tidy();
     }
 }
 
@@ -70,15 +503,14 @@ where
 ///
 /// assert_eq!(x + 1, y);
 /// ```
-impl<V> Add<V> for ArithMap<'_, V>
+impl<K, V, S> Add<V> for ArithMap<K, V, S>
 where
-    V: AddAssign + Copy,
+    S: Storage<K, V>,
+    V: for<'x> AddAssign<&'x V>,
 {
     type Output = Self;
-    fn add(mut self: Self, other: V) -> Self {
-        for v in self.hashmap.values_mut() {
-            *v += other;
-        }
+    fn add(mut self, other: V) -> Self {
+        self += other;
         self
     }
 }
@@ -91,14 +523,16 @@ where
 ///
 /// assert_eq!(x, y);
 /// ```
-impl<V> AddAssign<V> for ArithMap<'_, V>
+impl<K, V, S> AddAssign<V> for ArithMap<K, V, S>
 where
-    V: AddAssign + Copy,
+    S: Storage<K, V>,
+    V: for<'x> AddAssign<&'x V>,
 {
     fn add_assign(&mut self, other: V) {
-        for v in self.hashmap.values_mut() {
-            *v += other;
+        for (_, v) in self.storage.iter_mut() {
+            *v += &other;
         }
+        self.storage.tidy();
     }
 }
 
@@ -109,15 +543,14 @@ where
 ///
 /// assert_eq!(x - 1, y);
 /// ```
-impl<V> Sub<V> for ArithMap<'_, V>
+impl<K, V, S> Sub<V> for ArithMap<K, V, S>
 where
-    V: SubAssign + Copy,
+    S: Storage<K, V>,
+    V: for<'x> SubAssign<&'x V>,
 {
     type Output = Self;
-    fn sub(mut self: Self, other: V) -> Self {
-        for v in self.hashmap.values_mut() {
-            *v -= other;
-        }
+    fn sub(mut self, other: V) -> Self {
+        self -= other;
         self
     }
 }
@@ -130,14 +563,16 @@ where
 ///
 /// assert_eq!(x, y);
 /// ```
-impl<V> SubAssign<V> for ArithMap<'_, V>
+impl<K, V, S> SubAssign<V> for ArithMap<K, V, S>
 where
-    V: SubAssign + Copy,
+    S: Storage<K, V>,
+    V: for<'x> SubAssign<&'x V>,
 {
     fn sub_assign(&mut self, other: V) {
-        for v in self.hashmap.values_mut() {
-            *v -= other;
+        for (_, v) in self.storage.iter_mut() {
+            *v -= &other;
         }
+        self.storage.tidy();
     }
 }
 
@@ -148,15 +583,14 @@ where
 ///
 /// assert_eq!(x * 2.0, y);
 /// ```
-impl<V> Mul<V> for ArithMap<'_, V>
+impl<K, V, S> Mul<V> for ArithMap<K, V, S>
 where
-    V: MulAssign + Copy,
+    S: Storage<K, V>,
+    V: for<'x> MulAssign<&'x V>,
 {
     type Output = Self;
-    fn mul(mut self: Self, other: V) -> Self {
-        for v in self.hashmap.values_mut() {
-            *v *= other;
-        }
+    fn mul(mut self, other: V) -> Self {
+        self *= other;
         self
     }
 }
@@ -169,14 +603,96 @@ where
 ///
 /// assert_eq!(x, y);
 /// ```
-impl<V> MulAssign<V> for ArithMap<'_, V>
+impl<K, V, S> MulAssign<V> for ArithMap<K, V, S>
 where
-    V: MulAssign + Copy,
+    S: Storage<K, V>,
+    V: for<'x> MulAssign<&'x V>,
 {
     fn mul_assign(&mut self, other: V) {
-        for v in self.hashmap.values_mut() {
-            *v *= other;
+        for (_, v) in self.storage.iter_mut() {
+            *v *= &other;
         }
+        self.storage.tidy();
+    }
+}
+
+/// ```
+/// # use arith::*;
+/// let x = arithmap!{"a" => 6, "b" => 4};
+/// let y = arithmap!{"a" => 3, "b" => 2};
+///
+/// assert_eq!(x / 2, y);
+/// ```
+impl<K, V, S> Div<V> for ArithMap<K, V, S>
+where
+    S: Storage<K, V>,
+    V: for<'x> DivAssign<&'x V>,
+{
+    type Output = Self;
+    fn div(mut self, other: V) -> Self {
+        self /= other;
+        self
+    }
+}
+
+/// ```
+/// # use arith::*;
+/// let mut x = arithmap!{"a" => 6, "b" => 4};
+/// x /= 2;
+/// let y = arithmap!{"a" => 3, "b" => 2};
+///
+/// assert_eq!(x, y);
+/// ```
+impl<K, V, S> DivAssign<V> for ArithMap<K, V, S>
+where
+    S: Storage<K, V>,
+    V: for<'x> DivAssign<&'x V>,
+{
+    fn div_assign(&mut self, other: V) {
+        for (_, v) in self.storage.iter_mut() {
+            *v /= &other;
+        }
+        self.storage.tidy();
+    }
+}
+
+/// ```
+/// # use arith::*;
+/// let x = arithmap!{"a" => 7, "b" => 4};
+/// let y = arithmap!{"a" => 1, "b" => 0};
+///
+/// assert_eq!(x % 2, y);
+/// ```
+impl<K, V, S> Rem<V> for ArithMap<K, V, S>
+where
+    S: Storage<K, V>,
+    V: for<'x> RemAssign<&'x V>,
+{
+    type Output = Self;
+    fn rem(mut self, other: V) -> Self {
+        self %= other;
+        self
+    }
+}
+
+/// ```
+/// # use arith::*;
+/// let mut x = arithmap!{"a" => 7, "b" => 4};
+/// x %= 2;
+/// let y = arithmap!{"a" => 1, "b" => 0};
+///
+/// assert_eq!(x, y);
+/// ```
+impl<K, V, S> RemAssign<V> for ArithMap<K, V, S>
+where
+    S: Storage<K, V>,
+    V: for<'x> RemAssign<&'x V>,
+{
+    fn rem_assign(&mut self, other: V) {
+        for (_, v) in self.storage.iter_mut() {
+            *v %= &other;
+        }
+        self.storage.tidy();
     }
 }
 
@@ -188,24 +704,41 @@ where
 ///
 /// assert_eq!(x + y, z);
 /// ```
-impl<V> Add for ArithMap<'_, V>
+impl<K, V, S> Add for ArithMap<K, V, S>
 where
-    V: Add<Output = V> + AddAssign + Copy + Default,
+    S: Storage<K, V>,
+    V: AddAssign,
 {
     type Output = Self;
-    fn add(self: Self, other: Self) -> Self {
-        let mut r: Self = Default::default();
-        for (k, v) in self.hashmap.iter() {
-            r.hashmap.insert(*k, *v);
-        }
-        for (k, v2) in other.hashmap.iter() {
-            if let Some(v1) = r.hashmap.get_mut(k) {
-                *v1 += *v2;
+    fn add(mut self, other: Self) -> Self {
+        self += other;
+        self
+    }
+}
+
+/// ```
+/// # use arith::*;
+/// let mut x = arithmap!{"a" => 1, "b" => 2};
+/// let y = arithmap!{"b" => 2, "c" => 3};
+/// x += y;
+/// let z = arithmap!{"a" => 1, "b" => 4, "c" => 3};
+///
+/// assert_eq!(x, z);
+/// ```
+impl<K, V, S> AddAssign for ArithMap<K, V, S>
+where
+    S: Storage<K, V>,
+    V: AddAssign,
+{
+    fn add_assign(&mut self, other: Self) {
+        for (k, v2) in other.storage {
+            if let Some(v1) = self.storage.get_mut(&k) {
+                *v1 += v2;
             } else {
-                r.hashmap.insert(*k, *v2);
+                self.storage.insert(k, v2);
             }
         }
-        r
+        self.storage.tidy();
     }
 }
 
@@ -213,23 +746,26 @@ where
 /// # use arith::*;
 /// let mut x = arithmap!{"a" => 1, "b" => 2};
 /// let y = arithmap!{"b" => 2, "c" => 3};
-/// x += y;
+/// x += &y;
 /// let z = arithmap!{"a" => 1, "b" => 4, "c" => 3};
 ///
 /// assert_eq!(x, z);
 /// ```
-impl<V> AddAssign for ArithMap<'_, V>
+impl<K, V, S> AddAssign<&ArithMap<K, V, S>> for ArithMap<K, V, S>
 where
-    V: Add<Output = V> + AddAssign + Copy + Default,
+    S: Storage<K, V>,
+    K: Clone,
+    V: for<'x> AddAssign<&'x V> + Clone,
 {
-    fn add_assign(&mut self, other: Self) {
-        for (k, v2) in other.hashmap.iter() {
-            if let Some(v1) = self.hashmap.get_mut(k) {
-                *v1 += *v2;
+    fn add_assign(&mut self, other: &Self) {
+        for (k, v2) in other.storage.iter() {
+            if let Some(v1) = self.storage.get_mut(k) {
+                *v1 += v2;
             } else {
-                self.hashmap.insert(*k, *v2);
+                self.storage.insert(k.clone(), v2.clone());
             }
         }
+        self.storage.tidy();
     }
 }
 
@@ -241,25 +777,43 @@ where
 ///
 /// assert_eq!(x - y, z);
 /// ```
-impl<V> Sub for ArithMap<'_, V>
+impl<K, V, S> Sub for ArithMap<K, V, S>
 where
-    V: Sub<Output = V> + SubAssign + Copy + Default,
+    S: Storage<K, V>,
+    V: SubAssign + Zero,
 {
     type Output = Self;
-    fn sub(self: Self, other: Self) -> Self {
-        let zero: V = Default::default();
-        let mut r: Self = Default::default();
-        for (k, v) in self.hashmap.iter() {
-            r.hashmap.insert(*k, *v);
-        }
-        for (k, v2) in other.hashmap.iter() {
-            if let Some(v1) = r.hashmap.get_mut(k) {
-                *v1 -= *v2;
+    fn sub(mut self, other: Self) -> Self {
+        self -= other;
+        self
+    }
+}
+
+/// ```
+/// # use arith::*;
+/// let mut x = arithmap!{"a" => 1, "b" => 2};
+/// let y = arithmap!{"b" => 2, "c" => 3};
+/// x -= y;
+/// let z = arithmap!{"a" => 1, "b" => 0, "c" => -3};
+///
+/// assert_eq!(x, z);
+/// ```
+impl<K, V, S> SubAssign for ArithMap<K, V, S>
+where
+    S: Storage<K, V>,
+    V: SubAssign + Zero,
+{
+    fn sub_assign(&mut self, other: Self) {
+        for (k, v2) in other.storage {
+            if let Some(v1) = self.storage.get_mut(&k) {
+                *v1 -= v2;
             } else {
-                r.hashmap.insert(*k, zero - *v2);
+                let mut v = V::zero();
+                v -= v2;
+                self.storage.insert(k, v);
             }
         }
-        r
+        self.storage.tidy();
     }
 }
 
@@ -267,23 +821,318 @@ where
 /// # use arith::*;
 /// let mut x = arithmap!{"a" => 1, "b" => 2};
 /// let y = arithmap!{"b" => 2, "c" => 3};
-/// x -= y;
+/// x -= &y;
 /// let z = arithmap!{"a" => 1, "b" => 0, "c" => -3};
 ///
 /// assert_eq!(x, z);
 /// ```
-impl<V> SubAssign for ArithMap<'_, V>
+impl<K, V, S> SubAssign<&ArithMap<K, V, S>> for ArithMap<K, V, S>
 where
-    V: Sub<Output = V> + SubAssign + Copy + Default,
+    S: Storage<K, V>,
+    K: Clone,
+    V: for<'x> SubAssign<&'x V> + Zero,
 {
-    fn sub_assign(&mut self, other: Self) {
-        let zero: V = Default::default();
-        for (k, v2) in other.hashmap.iter() {
-            if let Some(v1) = self.hashmap.get_mut(k) {
-                *v1 -= *v2;
+    fn sub_assign(&mut self, other: &Self) {
+        for (k, v2) in other.storage.iter() {
+            if let Some(v1) = self.storage.get_mut(k) {
+                *v1 -= v2;
             } else {
-                self.hashmap.insert(*k, zero - *v2);
+                let mut v = V::zero();
+                v -= v2;
+                self.storage.insert(k.clone(), v);
             }
         }
+        self.storage.tidy();
+    }
+}
+
+/// Keys missing from either side are zero, so only keys present in both maps are kept; use
+/// [`ArithMap::combine`] for other joins.
+///
+/// ```
+/// # use arith::*;
+/// let x = arithmap!{"a" => 1, "b" => 2};
+/// let y = arithmap!{"b" => 3, "c" => 4};
+/// let z = arithmap!{"b" => 6};
+///
+/// assert_eq!(x * y, z);
+/// ```
+impl<K, V, S> Mul for ArithMap<K, V, S>
+where
+    S: Storage<K, V>,
+    V: for<'x> MulAssign<&'x V>,
+{
+    type Output = Self;
+    fn mul(mut self, other: Self) -> Self {
+        self *= other;
+        self
+    }
+}
+
+/// ```
+/// # use arith::*;
+/// let mut x = arithmap!{"a" => 1, "b" => 2};
+/// let y = arithmap!{"b" => 3, "c" => 4};
+/// x *= y;
+/// let z = arithmap!{"b" => 6};
+///
+/// assert_eq!(x, z);
+/// ```
+impl<K, V, S> MulAssign for ArithMap<K, V, S>
+where
+    S: Storage<K, V>,
+    V: for<'x> MulAssign<&'x V>,
+{
+    fn mul_assign(&mut self, other: Self) {
+        *self *= &other;
+    }
+}
+
+/// ```
+/// # use arith::*;
+/// let mut x = arithmap!{"a" => 1, "b" => 2};
+/// let y = arithmap!{"b" => 3, "c" => 4};
+/// x *= &y;
+/// let z = arithmap!{"b" => 6};
+///
+/// assert_eq!(x, z);
+/// ```
+impl<K, V, S> MulAssign<&ArithMap<K, V, S>> for ArithMap<K, V, S>
+where
+    S: Storage<K, V>,
+    V: for<'x> MulAssign<&'x V>,
+{
+    fn mul_assign(&mut self, other: &Self) {
+        self.storage.retain(|k, v1| match other.storage.get(k) {
+            Some(v2) => {
+                *v1 *= v2;
+                true
+            }
+            None => false,
+        });
+        self.storage.tidy();
+    }
+}
+
+/// Like `*`, keeps only the keys present on both sides: keys missing or zero on the
+/// right-hand side are removed; use [`ArithMap::div_or`] to handle them differently.
+///
+/// ```
+/// # use arith::*;
+/// assert_eq!(arithmap!{"a" => 6, "b" => 2} / arithmap!{"a" => 3, "b" => 0}, arithmap!{"a" => 2});
+///
+/// let x = arithmap!{"a" => 6, "b" => 4};
+/// let y = arithmap!{"a" => 3, "b" => 2, "c" => 1};
+/// let z = arithmap!{"a" => 2, "b" => 2};
+///
+/// assert_eq!(x / y, z);
+/// ```
+impl<K, V, S> Div for ArithMap<K, V, S>
+where
+    S: Storage<K, V>,
+    V: for<'x> DivAssign<&'x V> + Zero,
+{
+    type Output = Self;
+    fn div(mut self, other: Self) -> Self {
+        self /= &other;
+        self
+    }
+}
+
+/// ```
+/// # use arith::*;
+/// let mut x = arithmap!{"a" => 6, "b" => 4};
+/// let y = arithmap!{"a" => 3, "b" => 2, "c" => 1};
+/// x /= y;
+/// let z = arithmap!{"a" => 2, "b" => 2};
+///
+/// assert_eq!(x, z);
+/// ```
+impl<K, V, S> DivAssign for ArithMap<K, V, S>
+where
+    S: Storage<K, V>,
+    V: for<'x> DivAssign<&'x V> + Zero,
+{
+    fn div_assign(&mut self, other: Self) {
+        *self /= &other;
+    }
+}
+
+/// ```
+/// # use arith::*;
+/// let mut x = arithmap!{"a" => 6, "b" => 4};
+/// let y = arithmap!{"a" => 3, "b" => 2, "c" => 1};
+/// x /= &y;
+///
+/// assert_eq!(x, arithmap!{"a" => 6 / 3, "b" => 4 / 2});
+/// ```
+impl<K, V, S> DivAssign<&ArithMap<K, V, S>> for ArithMap<K, V, S>
+where
+    S: Storage<K, V>,
+    V: for<'x> DivAssign<&'x V> + Zero,
+{
+    fn div_assign(&mut self, other: &Self) {
+        self.divide(other, |v1, v2| *v1 /= v2, |_| false);
+    }
+}
+
+/// Like `*`, keeps only the keys present on both sides: keys missing or zero on the
+/// right-hand side are removed; use [`ArithMap::rem_or`] to handle them differently.
+///
+/// ```
+/// # use arith::*;
+/// assert_eq!(arithmap!{"a" => 7, "b" => 2} % arithmap!{"a" => 3, "b" => 0}, arithmap!{"a" => 1});
+///
+/// let x = arithmap!{"a" => 7, "b" => 4};
+/// let y = arithmap!{"a" => 3, "b" => 2, "c" => 1};
+/// let z = arithmap!{"a" => 1, "b" => 0};
+///
+/// assert_eq!(x % y, z);
+/// ```
+impl<K, V, S> Rem for ArithMap<K, V, S>
+where
+    S: Storage<K, V>,
+    V: for<'x> RemAssign<&'x V> + Zero,
+{
+    type Output = Self;
+    fn rem(mut self, other: Self) -> Self {
+        self %= &other;
+        self
+    }
+}
+
+/// ```
+/// # use arith::*;
+/// let mut x = arithmap!{"a" => 7, "b" => 4};
+/// let y = arithmap!{"a" => 3, "b" => 2, "c" => 1};
+/// x %= y;
+/// let z = arithmap!{"a" => 1, "b" => 0};
+///
+/// assert_eq!(x, z);
+/// ```
+impl<K, V, S> RemAssign for ArithMap<K, V, S>
+where
+    S: Storage<K, V>,
+    V: for<'x> RemAssign<&'x V> + Zero,
+{
+    fn rem_assign(&mut self, other: Self) {
+        *self %= &other;
+    }
+}
+
+/// ```
+/// # use arith::*;
+/// let mut x = arithmap!{"a" => 6, "b" => 4};
+/// let y = arithmap!{"a" => 3, "b" => 2, "c" => 1};
+/// x %= &y;
+///
+/// assert_eq!(x, arithmap!{"a" => 6 % 3, "b" => 4 % 2});
+/// ```
+impl<K, V, S> RemAssign<&ArithMap<K, V, S>> for ArithMap<K, V, S>
+where
+    S: Storage<K, V>,
+    V: for<'x> RemAssign<&'x V> + Zero,
+{
+    fn rem_assign(&mut self, other: &Self) {
+        self.divide(other, |v1, v2| *v1 %= v2, |_| false);
+    }
+}
+
+/// ```
+/// # use arith::*;
+/// let x = arithmap!{"a" => 1, "b" => -2};
+/// let y = arithmap!{"a" => -1, "b" => 2};
+///
+/// assert_eq!(-x, y);
+/// ```
+impl<K, V, S> Neg for ArithMap<K, V, S>
+where
+    S: Storage<K, V>,
+    V: Neg<Output = V>,
+{
+    type Output = Self;
+    fn neg(self) -> Self {
+        let mut storage = S::default();
+        for (k, v) in self.storage {
+            storage.insert(k, -v);
+        }
+        ArithMap::from(storage)
+    }
+}
+
+/// Implements `a op &b`, `&a op b` and `&a op &b` on top of `a op= &b`, and `&a op k` on top
+/// of `a op= k`.
+macro_rules! forward_ref_binop {
+    (impl $imp:ident, $method:ident, $assign:ident where $($bound:tt)*) => {
+        impl<K, V, S> $imp<&ArithMap<K, V, S>> for ArithMap<K, V, S>
+        where
+            S: Storage<K, V>,
+            K: Clone,
+            $($bound)*
+        {
+            type Output = Self;
+            fn $method(mut self, other: &Self) -> Self {
+                self.$assign(other);
+                self
+            }
+        }
+
+        impl<K, V, S> $imp<ArithMap<K, V, S>> for &ArithMap<K, V, S>
+        where
+            S: Storage<K, V> + Clone,
+            K: Clone,
+            $($bound)*
+        {
+            type Output = ArithMap<K, V, S>;
+            fn $method(self, other: ArithMap<K, V, S>) -> ArithMap<K, V, S> {
+                let mut r = self.clone();
+                r.$assign(&other);
+                r
+            }
+        }
+
+        impl<K, V, S> $imp<&ArithMap<K, V, S>> for &ArithMap<K, V, S>
+        where
+            S: Storage<K, V> + Clone,
+            K: Clone,
+            $($bound)*
+        {
+            type Output = ArithMap<K, V, S>;
+            fn $method(self, other: &ArithMap<K, V, S>) -> ArithMap<K, V, S> {
+                let mut r = self.clone();
+                r.$assign(other);
+                r
+            }
+        }
+
+        impl<K, V, S> $imp<V> for &ArithMap<K, V, S>
+        where
+            S: Storage<K, V> + Clone,
+            $($bound)*
+        {
+            type Output = ArithMap<K, V, S>;
+            fn $method(self, other: V) -> ArithMap<K, V, S> {
+                let mut r = self.clone();
+                r.$assign(other);
+                r
+            }
+        }
+    };
+}
+
+forward_ref_binop!(impl Add, add, add_assign where V: for<'x> AddAssign<&'x V> + Clone);
+forward_ref_binop!(impl Sub, sub, sub_assign where V: for<'x> SubAssign<&'x V> + Zero);
+forward_ref_binop!(impl Mul, mul, mul_assign where V: for<'x> MulAssign<&'x V>);
+forward_ref_binop!(impl Div, div, div_assign where V: for<'x> DivAssign<&'x V> + Zero);
+forward_ref_binop!(impl Rem, rem, rem_assign where V: for<'x> RemAssign<&'x V> + Zero);
+
+impl<K, V, S> Neg for &ArithMap<K, V, S>
+where
+    S: Storage<K, V> + Clone,
+    V: Neg<Output = V>,
+{
+    type Output = ArithMap<K, V, S>;
+    fn neg(self) -> ArithMap<K, V, S> {
+        -self.clone()
     }
 }
@@ -0,0 +1,350 @@
+//! Sparse matrices as maps of rows.
+
+use std::fmt;
+use std::hash::Hash;
+use std::iter::FromIterator;
+use std::ops::{Add, AddAssign, Sub, SubAssign, Mul, MulAssign};
+
+use crate::num::Zero;
+use crate::ArithMap;
+
+/// A sparse matrix stored as rows keyed by `R`, each an [`ArithMap`] keyed by `C`.
+///
+/// Element-wise `+` and `-` and scalar `*` follow [`ArithMap`].
+///
+/// ```
+/// # use arith::*;
+/// let x: ArithMatrix<_, _, _> = vec![(("a", "x"), 1), (("a", "y"), 2), (("b", "y"), 3)]
+///     .into_iter()
+///     .collect();
+/// let y = ArithMatrix::from(arithmap!{"b" => arithmap!{"x" => 1, "y" => -3}});
+///
+/// assert_eq!(x.get(&"a", &"y"), Some(&2));
+/// assert_eq!(
+///     (x + y) * 2,
+///     ArithMatrix::from(arithmap!{"a" => arithmap!{"x" => 2, "y" => 4}, "b" => arithmap!{"x" => 2, "y" => 0}}),
+/// );
+/// ```
+pub struct ArithMatrix<R, C, V> {
+    pub rows: ArithMap<R, ArithMap<C, V>>,
+}
+
+impl<R, C, V> ArithMatrix<R, C, V>
+where
+    R: Hash + Eq,
+    C: Hash + Eq,
+{
+    /// ```
+    /// # use arith::*;
+    /// let x: ArithMatrix<&str, &str, i32> = ArithMatrix::new();
+    ///
+    /// assert!(x.rows.is_empty());
+    /// ```
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// ```
+    /// # use arith::*;
+    /// let x = ArithMatrix::from(arithmap!{"a" => arithmap!{"x" => 1}});
+    ///
+    /// assert_eq!(x.get(&"a", &"x"), Some(&1));
+    /// assert_eq!(x.get(&"a", &"y"), None);
+    /// ```
+    pub fn get(&self, row: &R, col: &C) -> Option<&V> {
+        self.rows.storage.get(row).and_then(|r| r.storage.get(col))
+    }
+
+    /// Rows sharing no column with `vector` are left out, as in [`ArithMatrix::matmul`].
+    ///
+    /// ```
+    /// # use arith::*;
+    /// let x = ArithMatrix::from(arithmap!{"a" => arithmap!{"x" => 1, "y" => 2}, "b" => arithmap!{"y" => 3}});
+    /// let y = arithmap!{"x" => 10, "y" => 100};
+    ///
+    /// assert_eq!(x.matvec(&y), arithmap!{"a" => 210, "b" => 300});
+    ///
+    /// let z = ArithMatrix::from(arithmap!{"c" => arithmap!{"z" => 1}});
+    /// assert!(z.matvec(&y).is_empty());
+    /// ```
+    pub fn matvec(&self, vector: &ArithMap<C, V>) -> ArithMap<R, V>
+    where
+        R: Clone,
+        V: AddAssign,
+        for<'x> &'x V: Mul<Output = V>,
+    {
+        let mut product: ArithMap<R, V> = ArithMap::new();
+        for (r, row) in self.rows.storage.iter() {
+            let mut out = None;
+            for (c, a) in row.storage.iter() {
+                if let Some(b) = vector.storage.get(c) {
+                    match &mut out {
+                        Some(out) => *out += a * b,
+                        None => out = Some(a * b),
+                    }
+                }
+            }
+            if let Some(out) = out {
+                product.storage.insert(r.clone(), out);
+            }
+        }
+        product
+    }
+
+    /// ```
+    /// # use arith::*;
+    /// let x = ArithMatrix::from(arithmap!{"a" => arithmap!{"x" => 1, "y" => 2}});
+    /// let y = ArithMatrix::from(arithmap!{"x" => arithmap!{0 => 3}, "y" => arithmap!{0 => 4, 1 => 5}});
+    ///
+    /// assert_eq!(x.matmul(&y), ArithMatrix::from(arithmap!{"a" => arithmap!{0 => 11, 1 => 10}}));
+    ///
+    /// let z = ArithMatrix::from(arithmap!{"b" => arithmap!{"z" => 1}});
+    /// assert!(z.matmul(&y).rows.is_empty());
+    /// ```
+    pub fn matmul<D>(&self, other: &ArithMatrix<C, D, V>) -> ArithMatrix<R, D, V>
+    where
+        R: Clone,
+        D: Hash + Eq + Clone,
+        V: AddAssign,
+        for<'x> &'x V: Mul<Output = V>,
+    {
+        let mut product = ArithMatrix::new();
+        for (r, row) in self.rows.storage.iter() {
+            let mut out = ArithMap::new();
+            for (c, a) in row.storage.iter() {
+                if let Some(col) = other.rows.storage.get(c) {
+                    out.extend(col.storage.iter().map(|(d, b)| (d.clone(), a * b)));
+                }
+            }
+            if !out.is_empty() {
+                product.rows.storage.insert(r.clone(), out);
+            }
+        }
+        product
+    }
+
+    /// ```
+    /// # use arith::*;
+    /// let x = ArithMatrix::from(arithmap!{"a" => arithmap!{"x" => 1, "y" => 2}});
+    ///
+    /// assert_eq!(x.transpose(), ArithMatrix::from(arithmap!{"x" => arithmap!{"a" => 1}, "y" => arithmap!{"a" => 2}}));
+    /// ```
+    pub fn transpose(self) -> ArithMatrix<C, R, V>
+    where
+        R: Clone,
+    {
+        let mut transpose = ArithMatrix::new();
+        for (r, row) in self.rows {
+            for (c, v) in row {
+                let col = transpose.rows.storage.entry(c).or_insert_with(ArithMap::new);
+                col.storage.insert(r.clone(), v);
+            }
+        }
+        transpose
+    }
+
+    /// ```
+    /// # use arith::*;
+    /// let x = ArithMatrix::from(arithmap!{"a" => arithmap!{"x" => 1, "y" => 2}, "b" => arithmap!{"y" => 3}});
+    ///
+    /// assert_eq!(x.row_sums(), arithmap!{"a" => 3, "b" => 3});
+    /// ```
+    pub fn row_sums(&self) -> ArithMap<R, V>
+    where
+        R: Clone,
+        V: for<'x> AddAssign<&'x V> + Zero,
+    {
+        let mut sums: ArithMap<R, V> = ArithMap::new();
+        for (r, row) in self.rows.storage.iter() {
+            sums.storage.insert(r.clone(), row.total());
+        }
+        sums
+    }
+
+    /// ```
+    /// # use arith::*;
+    /// let x = ArithMatrix::from(arithmap!{"a" => arithmap!{"x" => 1, "y" => 2}, "b" => arithmap!{"y" => 3}});
+    ///
+    /// assert_eq!(x.col_sums(), arithmap!{"x" => 1, "y" => 5});
+    /// ```
+    pub fn col_sums(&self) -> ArithMap<C, V>
+    where
+        C: Clone,
+        V: for<'x> AddAssign<&'x V> + Clone,
+    {
+        self.rows.storage.values().sum()
+    }
+}
+
+impl<R, C, V> From<ArithMap<R, ArithMap<C, V>>> for ArithMatrix<R, C, V> {
+    fn from(rows: ArithMap<R, ArithMap<C, V>>) -> Self {
+        ArithMatrix { rows }
+    }
+}
+
+/// ```
+/// # use arith::*;
+/// let x = ArithMatrix::from(arithmap!{("a", "x") => 1, ("b", "y") => 2});
+///
+/// assert_eq!(x, ArithMatrix::from(arithmap!{"a" => arithmap!{"x" => 1}, "b" => arithmap!{"y" => 2}}));
+/// ```
+impl<R, C, V> From<ArithMap<(R, C), V>> for ArithMatrix<R, C, V>
+where
+    R: Hash + Eq,
+    C: Hash + Eq,
+    V: AddAssign,
+{
+    fn from(map: ArithMap<(R, C), V>) -> Self {
+        map.into_iter().collect()
+    }
+}
+
+/// Values under repeated `(row, col)` keys are summed.
+impl<R, C, V> FromIterator<((R, C), V)> for ArithMatrix<R, C, V>
+where
+    R: Hash + Eq,
+    C: Hash + Eq,
+    V: AddAssign,
+{
+    fn from_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = ((R, C), V)>,
+    {
+        let mut matrix = ArithMatrix::new();
+        for ((r, c), v) in iter {
+            let row = matrix.rows.storage.entry(r).or_insert_with(ArithMap::new);
+            row.extend(Some((c, v)));
+        }
+        matrix
+    }
+}
+
+impl<R, C, V> Default for ArithMatrix<R, C, V> {
+    fn default() -> Self {
+        ArithMatrix { rows: Default::default() }
+    }
+}
+
+impl<R, C, V> Clone for ArithMatrix<R, C, V>
+where
+    R: Clone,
+    C: Clone,
+    V: Clone,
+{
+    fn clone(&self) -> Self {
+        ArithMatrix { rows: self.rows.clone() }
+    }
+}
+
+impl<R, C, V> PartialEq for ArithMatrix<R, C, V>
+where
+    R: Hash + Eq,
+    C: Hash + Eq,
+    V: PartialEq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.rows == other.rows
+    }
+}
+
+impl<R, C, V> Eq for ArithMatrix<R, C, V>
+where
+    R: Hash + Eq,
+    C: Hash + Eq,
+    V: Eq,
+{
+}
+
+impl<R, C, V> fmt::Debug for ArithMatrix<R, C, V>
+where
+    R: fmt::Debug,
+    C: fmt::Debug,
+    V: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.rows.fmt(f)
+    }
+}
+
+impl<R, C, V> Add for ArithMatrix<R, C, V>
+where
+    R: Hash + Eq,
+    C: Hash + Eq,
+    V: AddAssign,
+{
+    type Output = Self;
+    fn add(mut self, other: Self) -> Self {
+        self += other;
+        self
+    }
+}
+
+impl<R, C, V> AddAssign for ArithMatrix<R, C, V>
+where
+    R: Hash + Eq,
+    C: Hash + Eq,
+    V: AddAssign,
+{
+    fn add_assign(&mut self, other: Self) {
+        self.rows += other.rows;
+    }
+}
+
+/// ```
+/// # use arith::*;
+/// let x = ArithMatrix::from(arithmap!{"a" => arithmap!{"x" => 1}});
+/// let y = ArithMatrix::from(arithmap!{"a" => arithmap!{"y" => 2}, "b" => arithmap!{"x" => 3}});
+///
+/// assert_eq!(x - y, ArithMatrix::from(arithmap!{"a" => arithmap!{"x" => 1, "y" => -2}, "b" => arithmap!{"x" => -3}}));
+/// ```
+impl<R, C, V> Sub for ArithMatrix<R, C, V>
+where
+    R: Hash + Eq,
+    C: Hash + Eq,
+    V: SubAssign + Zero,
+{
+    type Output = Self;
+    fn sub(mut self, other: Self) -> Self {
+        self -= other;
+        self
+    }
+}
+
+impl<R, C, V> SubAssign for ArithMatrix<R, C, V>
+where
+    R: Hash + Eq,
+    C: Hash + Eq,
+    V: SubAssign + Zero,
+{
+    fn sub_assign(&mut self, other: Self) {
+        self.rows -= other.rows;
+    }
+}
+
+impl<R, C, V> Mul<V> for ArithMatrix<R, C, V>
+where
+    R: Hash + Eq,
+    C: Hash + Eq,
+    V: for<'x> MulAssign<&'x V>,
+{
+    type Output = Self;
+    fn mul(mut self, other: V) -> Self {
+        self *= other;
+        self
+    }
+}
+
+impl<R, C, V> MulAssign<V> for ArithMatrix<R, C, V>
+where
+    R: Hash + Eq,
+    C: Hash + Eq,
+    V: for<'x> MulAssign<&'x V>,
+{
+    fn mul_assign(&mut self, other: V) {
+        for (_, row) in &mut self.rows {
+            for (_, v) in row {
+                *v *= &other;
+            }
+        }
+    }
+}
@@ -0,0 +1,229 @@
+//! Numeric traits for value types.
+
+use std::ops::DivAssign;
+
+/// Additive identity, used wherever a map treats a missing key as zero.
+///
+/// ```
+/// # use arith::*;
+/// #[derive(Clone, Copy, Debug, PartialEq)]
+/// struct Kelvin(f64);
+///
+/// impl Default for Kelvin {
+///     fn default() -> Self {
+///         Kelvin(273.15)
+///     }
+/// }
+///
+/// impl Zero for Kelvin {
+///     fn zero() -> Self {
+///         Kelvin(0.0)
+///     }
+///
+///     fn is_zero(&self) -> bool {
+///         self.0 == 0.0
+///     }
+/// }
+///
+/// let mut x = arithmap!{"a" => Kelvin(0.0), "b" => Kelvin::default()};
+/// x.prune();
+///
+/// assert_eq!(x, arithmap!{"b" => Kelvin(273.15)});
+/// ```
+pub trait Zero: Sized {
+    fn zero() -> Self;
+
+    fn is_zero(&self) -> bool;
+}
+
+/// Multiplicative identity.
+///
+/// ```
+/// # use arith::*;
+/// # use std::ops::Mul;
+/// let x = arithmap!{"a" => 2, "b" => 3};
+/// let y = arithmap!{"b" => 4};
+///
+/// assert_eq!(x.combine(y, Join::Left(One::one()), Mul::mul), arithmap!{"a" => 2, "b" => 12});
+/// ```
+pub trait One: Sized {
+    fn one() -> Self;
+
+    fn is_one(&self) -> bool;
+}
+
+#[cfg(not(feature = "num-traits"))]
+macro_rules! impl_identities {
+    ($zero:expr, $one:expr; $($t:ty)*) => ($(
+        impl Zero for $t {
+            fn zero() -> Self {
+                $zero
+            }
+
+            #[allow(clippy::float_cmp)]
+            fn is_zero(&self) -> bool {
+                *self == $zero
+            }
+        }
+
+        impl One for $t {
+            fn one() -> Self {
+                $one
+            }
+
+            #[allow(clippy::float_cmp)]
+            fn is_one(&self) -> bool {
+                *self == $one
+            }
+        }
+    )*)
+}
+
+#[cfg(not(feature = "num-traits"))]
+impl_identities! { 0, 1; i8 i16 i32 i64 i128 isize u8 u16 u32 u64 u128 usize }
+#[cfg(not(feature = "num-traits"))]
+impl_identities! { 0.0, 1.0; f32 f64 }
+
+/// With the `num-traits` feature, every `num_traits::Zero` is a [`Zero`], so types from
+/// other crates, like `BigInt`, work with every operator:
+///
+/// ```
+/// # use arith::*;
+/// # use num_bigint::BigInt;
+/// let mut x = arithmap!{"a" => BigInt::from(2), "b" => BigInt::from(3)};
+/// x -= arithmap!{"a" => BigInt::from(2)};
+/// x.prune();
+///
+/// assert_eq!(x, arithmap!{"b" => BigInt::from(3)});
+/// ```
+#[cfg(feature = "num-traits")]
+impl<T> Zero for T
+where
+    T: num_traits::Zero,
+{
+    fn zero() -> Self {
+        num_traits::Zero::zero()
+    }
+
+    fn is_zero(&self) -> bool {
+        num_traits::Zero::is_zero(self)
+    }
+}
+
+/// With the `num-traits` feature, every `num_traits::One` is a [`One`].
+#[cfg(feature = "num-traits")]
+impl<T> One for T
+where
+    T: num_traits::One + PartialEq,
+{
+    fn one() -> Self {
+        num_traits::One::one()
+    }
+
+    fn is_one(&self) -> bool {
+        num_traits::One::is_one(self)
+    }
+}
+
+/// Conversion to `f64`, rounding where the value has no exact representation.
+///
+/// Unlike `Into<f64>`, this covers 64- and 128-bit integers, so norms and statistics work
+/// on any primitive:
+///
+/// ```
+/// # use arith::*;
+/// let x = arithmap!{"a" => 3u64, "b" => 4};
+///
+/// assert_eq!(x.l2(), 5.0);
+/// assert_eq!(u64::MAX.to_f64(), 2f64.powi(64));
+/// ```
+pub trait ToF64 {
+    fn to_f64(&self) -> f64;
+}
+
+macro_rules! impl_to_f64 {
+    ($($t:ty)*) => ($(
+        impl ToF64 for $t {
+            fn to_f64(&self) -> f64 {
+                *self as f64
+            }
+        }
+    )*)
+}
+
+impl_to_f64! { i8 i16 i32 i64 i128 isize u8 u16 u32 u64 u128 usize f32 f64 }
+
+/// Floating point values.
+pub trait Float: Copy + ToF64 + DivAssign {
+    /// Rounds to the nearest representable value.
+    fn from_f64(x: f64) -> Self;
+}
+
+impl Float for f32 {
+    fn from_f64(x: f64) -> Self {
+        x as f32
+    }
+}
+
+impl Float for f64 {
+    fn from_f64(x: f64) -> Self {
+        x
+    }
+}
+
+/// Primitive integers, with explicit overflow behaviour.
+pub trait Integer: Copy + Zero {
+    fn checked_add(self, rhs: Self) -> Option<Self>;
+    fn checked_sub(self, rhs: Self) -> Option<Self>;
+    fn checked_mul(self, rhs: Self) -> Option<Self>;
+    fn saturating_add(self, rhs: Self) -> Self;
+    fn saturating_sub(self, rhs: Self) -> Self;
+    fn saturating_mul(self, rhs: Self) -> Self;
+    fn wrapping_add(self, rhs: Self) -> Self;
+    fn wrapping_sub(self, rhs: Self) -> Self;
+    fn wrapping_mul(self, rhs: Self) -> Self;
+}
+
+macro_rules! impl_integer {
+    ($($t:ty)*) => ($(
+        impl Integer for $t {
+            fn checked_add(self, rhs: Self) -> Option<Self> {
+                <$t>::checked_add(self, rhs)
+            }
+
+            fn checked_sub(self, rhs: Self) -> Option<Self> {
+                <$t>::checked_sub(self, rhs)
+            }
+
+            fn checked_mul(self, rhs: Self) -> Option<Self> {
+                <$t>::checked_mul(self, rhs)
+            }
+
+            fn saturating_add(self, rhs: Self) -> Self {
+                <$t>::saturating_add(self, rhs)
+            }
+
+            fn saturating_sub(self, rhs: Self) -> Self {
+                <$t>::saturating_sub(self, rhs)
+            }
+
+            fn saturating_mul(self, rhs: Self) -> Self {
+                <$t>::saturating_mul(self, rhs)
+            }
+
+            fn wrapping_add(self, rhs: Self) -> Self {
+                <$t>::wrapping_add(self, rhs)
+            }
+
+            fn wrapping_sub(self, rhs: Self) -> Self {
+                <$t>::wrapping_sub(self, rhs)
+            }
+
+            fn wrapping_mul(self, rhs: Self) -> Self {
+                <$t>::wrapping_mul(self, rhs)
+            }
+        }
+    )*)
+}
+
+impl_integer! { i8 i16 i32 i64 i128 isize u8 u16 u32 u64 u128 usize }
@@ -0,0 +1,285 @@
+//! Arithmetic on integer maps with explicit overflow behaviour.
+
+use std::error::Error;
+use std::fmt;
+
+use crate::num::Integer;
+use crate::{ArithMap, Join, Storage};
+
+/// A checked operation overflowed at `key`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Overflow<K> {
+    pub key: K,
+}
+
+impl<K> fmt::Display for Overflow<K>
+where
+    K: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "arithmetic overflow at key {:?}", self.key)
+    }
+}
+
+impl<K> Error for Overflow<K> where K: fmt::Debug {}
+
+impl<K, V, S> ArithMap<K, V, S>
+where
+    S: Storage<K, V>,
+    K: Clone,
+    V: Integer,
+{
+    /// ```
+    /// # use arith::*;
+    /// assert_eq!(arithmap!{"a" => 1u8, "b" => 2}.checked_add(250), Ok(arithmap!{"a" => 251, "b" => 252}));
+    /// assert_eq!(arithmap!{"a" => 1u8, "b" => 6}.checked_add(250), Err(Overflow { key: "b" }));
+    /// ```
+    pub fn checked_add(mut self, other: V) -> Result<Self, Overflow<K>> {
+        for (k, v) in self.storage.iter_mut() {
+            *v = v.checked_add(other).ok_or_else(|| Overflow { key: k.clone() })?;
+        }
+        self.storage.tidy();
+        Ok(self)
+    }
+
+    /// ```
+    /// # use arith::*;
+    /// assert_eq!(arithmap!{"a" => 5u8, "b" => 2}.checked_sub(2), Ok(arithmap!{"a" => 3, "b" => 0}));
+    /// assert_eq!(arithmap!{"a" => 5u8, "b" => 1}.checked_sub(2), Err(Overflow { key: "b" }));
+    /// ```
+    pub fn checked_sub(mut self, other: V) -> Result<Self, Overflow<K>> {
+        for (k, v) in self.storage.iter_mut() {
+            *v = v.checked_sub(other).ok_or_else(|| Overflow { key: k.clone() })?;
+        }
+        self.storage.tidy();
+        Ok(self)
+    }
+
+    /// ```
+    /// # use arith::*;
+    /// assert_eq!(arithmap!{"a" => 5u8, "b" => 2}.checked_mul(20), Ok(arithmap!{"a" => 100, "b" => 40}));
+    /// assert_eq!(arithmap!{"a" => 5u8, "b" => 20}.checked_mul(20), Err(Overflow { key: "b" }));
+    /// ```
+    pub fn checked_mul(mut self, other: V) -> Result<Self, Overflow<K>> {
+        for (k, v) in self.storage.iter_mut() {
+            *v = v.checked_mul(other).ok_or_else(|| Overflow { key: k.clone() })?;
+        }
+        self.storage.tidy();
+        Ok(self)
+    }
+
+    /// ```
+    /// # use arith::*;
+    /// assert_eq!(arithmap!{"a" => 1u8, "b" => 2}.checked_add_map(arithmap!{"b" => 3, "c" => 4}), Ok(arithmap!{"a" => 1, "b" => 5, "c" => 4}));
+    /// assert_eq!(arithmap!{"a" => 1u8, "b" => 2}.checked_add_map(arithmap!{"b" => 254}), Err(Overflow { key: "b" }));
+    /// ```
+    pub fn checked_add_map(self, other: Self) -> Result<Self, Overflow<K>> {
+        self.try_combine(other, Join::outer(V::zero()), V::checked_add)
+    }
+
+    /// ```
+    /// # use arith::*;
+    /// assert_eq!(arithmap!{"a" => 1i8, "b" => 2}.checked_sub_map(arithmap!{"b" => 3, "c" => 4}), Ok(arithmap!{"a" => 1, "b" => -1, "c" => -4}));
+    /// assert_eq!(arithmap!{"a" => 1u8, "b" => 2}.checked_sub_map(arithmap!{"c" => 1}), Err(Overflow { key: "c" }));
+    /// ```
+    pub fn checked_sub_map(self, other: Self) -> Result<Self, Overflow<K>> {
+        self.try_combine(other, Join::outer(V::zero()), V::checked_sub)
+    }
+
+    /// ```
+    /// # use arith::*;
+    /// assert_eq!(arithmap!{"a" => 1u8, "b" => 2}.checked_mul_map(arithmap!{"b" => 3, "c" => 4}), Ok(arithmap!{"b" => 6}));
+    /// assert_eq!(arithmap!{"a" => 1u8, "b" => 2}.checked_mul_map(arithmap!{"b" => 200}), Err(Overflow { key: "b" }));
+    /// ```
+    pub fn checked_mul_map(self, other: Self) -> Result<Self, Overflow<K>> {
+        self.try_combine(other, Join::Inner, V::checked_mul)
+    }
+
+    /// ```
+    /// # use arith::*;
+    /// let x = arithmap!{"a" => 1u8, "b" => 6};
+    ///
+    /// assert_eq!(x.saturating_add(250).storage["b"], 255);
+    /// ```
+    pub fn saturating_add(mut self, other: V) -> Self {
+        for (_, v) in self.storage.iter_mut() {
+            *v = v.saturating_add(other);
+        }
+        self.storage.tidy();
+        self
+    }
+
+    /// ```
+    /// # use arith::*;
+    /// let x = arithmap!{"a" => 1u8, "b" => 6};
+    ///
+    /// assert_eq!(x.saturating_sub(7).storage["b"], 0);
+    /// ```
+    pub fn saturating_sub(mut self, other: V) -> Self {
+        for (_, v) in self.storage.iter_mut() {
+            *v = v.saturating_sub(other);
+        }
+        self.storage.tidy();
+        self
+    }
+
+    /// ```
+    /// # use arith::*;
+    /// let x = arithmap!{"a" => 1u8, "b" => 20};
+    ///
+    /// assert_eq!(x.saturating_mul(20).storage["b"], 255);
+    /// ```
+    pub fn saturating_mul(mut self, other: V) -> Self {
+        for (_, v) in self.storage.iter_mut() {
+            *v = v.saturating_mul(other);
+        }
+        self.storage.tidy();
+        self
+    }
+
+    /// ```
+    /// # use arith::*;
+    /// let x = arithmap!{"a" => 1u8, "b" => 6};
+    /// let y = arithmap!{"b" => 250u8};
+    ///
+    /// assert_eq!(x.saturating_add_map(y).storage["b"], 255);
+    /// ```
+    pub fn saturating_add_map(self, other: Self) -> Self {
+        self.combine(other, Join::outer(V::zero()), V::saturating_add)
+    }
+
+    /// ```
+    /// # use arith::*;
+    /// let x = arithmap!{"a" => 1u8, "b" => 6};
+    /// let y = arithmap!{"b" => 7u8};
+    ///
+    /// assert_eq!(x.saturating_sub_map(y).storage["b"], 0);
+    /// ```
+    pub fn saturating_sub_map(self, other: Self) -> Self {
+        self.combine(other, Join::outer(V::zero()), V::saturating_sub)
+    }
+
+    /// ```
+    /// # use arith::*;
+    /// let x = arithmap!{"a" => 1u8, "b" => 20};
+    /// let y = arithmap!{"b" => 20u8};
+    ///
+    /// assert_eq!(x.saturating_mul_map(y).storage["b"], 255);
+    /// ```
+    pub fn saturating_mul_map(self, other: Self) -> Self {
+        self.combine(other, Join::Inner, V::saturating_mul)
+    }
+
+    /// ```
+    /// # use arith::*;
+    /// let x = arithmap!{"a" => 1u8, "b" => 6};
+    ///
+    /// assert_eq!(x.wrapping_add(250).storage["b"], 0);
+    /// ```
+    pub fn wrapping_add(mut self, other: V) -> Self {
+        for (_, v) in self.storage.iter_mut() {
+            *v = v.wrapping_add(other);
+        }
+        self.storage.tidy();
+        self
+    }
+
+    /// ```
+    /// # use arith::*;
+    /// let x = arithmap!{"a" => 1u8, "b" => 6};
+    ///
+    /// assert_eq!(x.wrapping_sub(7).storage["b"], 255);
+    /// ```
+    pub fn wrapping_sub(mut self, other: V) -> Self {
+        for (_, v) in self.storage.iter_mut() {
+            *v = v.wrapping_sub(other);
+        }
+        self.storage.tidy();
+        self
+    }
+
+    /// ```
+    /// # use arith::*;
+    /// let x = arithmap!{"a" => 1u8, "b" => 20};
+    ///
+    /// assert_eq!(x.wrapping_mul(20).storage["b"], 144);
+    /// ```
+    pub fn wrapping_mul(mut self, other: V) -> Self {
+        for (_, v) in self.storage.iter_mut() {
+            *v = v.wrapping_mul(other);
+        }
+        self.storage.tidy();
+        self
+    }
+
+    /// ```
+    /// # use arith::*;
+    /// let x = arithmap!{"a" => 1u8, "b" => 6};
+    /// let y = arithmap!{"b" => 250u8};
+    ///
+    /// assert_eq!(x.wrapping_add_map(y).storage["b"], 0);
+    /// ```
+    pub fn wrapping_add_map(self, other: Self) -> Self {
+        self.combine(other, Join::outer(V::zero()), V::wrapping_add)
+    }
+
+    /// ```
+    /// # use arith::*;
+    /// let x = arithmap!{"a" => 1u8, "b" => 6};
+    /// let y = arithmap!{"b" => 7u8};
+    ///
+    /// assert_eq!(x.wrapping_sub_map(y).storage["b"], 255);
+    /// ```
+    pub fn wrapping_sub_map(self, other: Self) -> Self {
+        self.combine(other, Join::outer(V::zero()), V::wrapping_sub)
+    }
+
+    /// ```
+    /// # use arith::*;
+    /// let x = arithmap!{"a" => 1u8, "b" => 20};
+    /// let y = arithmap!{"b" => 20u8};
+    ///
+    /// assert_eq!(x.wrapping_mul_map(y).storage["b"], 144);
+    /// ```
+    pub fn wrapping_mul_map(self, other: Self) -> Self {
+        self.combine(other, Join::Inner, V::wrapping_mul)
+    }
+
+    fn try_combine<F>(mut self, other: Self, join: Join<V>, mut f: F) -> Result<Self, Overflow<K>>
+    where
+        F: FnMut(V, V) -> Option<V>,
+    {
+        let (left, right) = join.fills();
+        let mut overflow = None;
+        self.storage.retain(|k, v1| {
+            if overflow.is_some() {
+                return true;
+            }
+            let v2 = match (other.storage.get(k), right) {
+                (Some(&v2), _) => v2,
+                (None, Some(fill)) => fill,
+                (None, None) => return false,
+            };
+            match f(*v1, v2) {
+                Some(v) => *v1 = v,
+                None => overflow = Some(k.clone()),
+            }
+            true
+        });
+        if let Some(key) = overflow {
+            return Err(Overflow { key });
+        }
+        if let Some(fill) = left {
+            for (k, v2) in other.storage {
+                if self.storage.get(&k).is_none() {
+                    match f(fill, v2) {
+                        Some(v) => self.storage.insert(k, v),
+                        None => return Err(Overflow { key: k }),
+                    };
+                }
+            }
+        }
+        self.storage.tidy();
+        Ok(self)
+    }
+}
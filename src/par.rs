@@ -0,0 +1,275 @@
+//! Parallel arithmetic on large maps, behind the `rayon` feature.
+//!
+//! Every value is computed by the same sequence of operations as on the serial path, so
+//! results are identical, floats included.
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::ops::{AddAssign, DivAssign, MulAssign, RemAssign, SubAssign};
+
+use rayon::iter::{IntoParallelIterator, IntoParallelRefIterator, ParallelIterator};
+use rayon::slice::ParallelSliceMut;
+
+use crate::num::Zero;
+use crate::prune::Pruned;
+use crate::sparse::Sparse;
+use crate::{ArithMap, Storage};
+
+impl<K, V, S> ArithMap<K, V, S>
+where
+    S: Storage<K, V> + Sync,
+    for<'a> &'a mut S: IntoParallelIterator<Item = (&'a K, &'a mut V)>,
+    K: Sync,
+    V: Send + Sync,
+{
+    /// ```
+    /// # use arith::*;
+    /// let x = arithmap!{"a" => 1, "b" => 2};
+    ///
+    /// assert_eq!(x.par_add(1), arithmap!{"a" => 2, "b" => 3});
+    /// ```
+    pub fn par_add(mut self, other: V) -> Self
+    where
+        V: for<'x> AddAssign<&'x V>,
+    {
+        self.par_apply(|v| *v += &other);
+        self
+    }
+
+    /// ```
+    /// # use arith::*;
+    /// let x = arithmap!{"a" => 1, "b" => 2};
+    ///
+    /// assert_eq!(x.par_sub(1), arithmap!{"a" => 0, "b" => 1});
+    /// ```
+    pub fn par_sub(mut self, other: V) -> Self
+    where
+        V: for<'x> SubAssign<&'x V>,
+    {
+        self.par_apply(|v| *v -= &other);
+        self
+    }
+
+    /// ```
+    /// # use arith::*;
+    /// let x = arithmap!{"a" => 1, "b" => 2};
+    ///
+    /// assert_eq!(x.par_mul(2), arithmap!{"a" => 2, "b" => 4});
+    /// ```
+    pub fn par_mul(mut self, other: V) -> Self
+    where
+        V: for<'x> MulAssign<&'x V>,
+    {
+        self.par_apply(|v| *v *= &other);
+        self
+    }
+
+    /// ```
+    /// # use arith::*;
+    /// let x = arithmap!{"a" => 2, "b" => 4};
+    ///
+    /// assert_eq!(x.par_div(2), arithmap!{"a" => 1, "b" => 2});
+    /// ```
+    pub fn par_div(mut self, other: V) -> Self
+    where
+        V: for<'x> DivAssign<&'x V>,
+    {
+        self.par_apply(|v| *v /= &other);
+        self
+    }
+
+    /// ```
+    /// # use arith::*;
+    /// let x = arithmap!{"a" => 3, "b" => 4};
+    ///
+    /// assert_eq!(x.par_rem(2), arithmap!{"a" => 1, "b" => 0});
+    /// ```
+    pub fn par_rem(mut self, other: V) -> Self
+    where
+        V: for<'x> RemAssign<&'x V>,
+    {
+        self.par_apply(|v| *v %= &other);
+        self
+    }
+
+    /// Map-wise `+`, updating shared keys in parallel.
+    ///
+    /// ```
+    /// # use arith::*;
+    /// let x = arithmap!{"a" => 1, "b" => 2};
+    /// let y = arithmap!{"b" => 2, "c" => 3};
+    ///
+    /// assert_eq!(x.par_add_map(&y), arithmap!{"a" => 1, "b" => 4, "c" => 3});
+    /// ```
+    pub fn par_add_map(mut self, other: &Self) -> Self
+    where
+        K: Clone,
+        V: for<'x> AddAssign<&'x V> + Clone,
+    {
+        self.par_merge(other, |v1, v2| *v1 += v2, V::clone);
+        self
+    }
+
+    /// Map-wise `-`, updating shared keys in parallel.
+    ///
+    /// ```
+    /// # use arith::*;
+    /// let x = arithmap!{"a" => 1, "b" => 2};
+    /// let y = arithmap!{"b" => 2, "c" => 3};
+    ///
+    /// assert_eq!(x.par_sub_map(&y), arithmap!{"a" => 1, "b" => 0, "c" => -3});
+    /// ```
+    pub fn par_sub_map(mut self, other: &Self) -> Self
+    where
+        K: Clone,
+        V: for<'x> SubAssign<&'x V> + Zero,
+    {
+        self.par_merge(other, |v1, v2| *v1 -= v2, |v2| {
+            let mut v = V::zero();
+            v -= v2;
+            v
+        });
+        self
+    }
+
+    /// Sums many maps, equal to `maps.iter().sum()`.
+    ///
+    /// Keys are sharded by hash, and each shard adds up its keys' values in parallel with
+    /// the others, still in the order of `maps` and tidying after each one.
+    ///
+    /// ```
+    /// # use arith::*;
+    /// let x = vec![arithmap!{"a" => 0.1}, arithmap!{"a" => 0.2, "b" => 1.0}, arithmap!{"a" => 0.3}];
+    ///
+    /// assert_eq!(ArithMap::<_, _>::par_sum(&x), x.iter().sum());
+    ///
+    /// let y: Vec<_> = [1.0, -1.0 + 1e-11, 5.0]
+    ///     .iter()
+    ///     .map(|&v| arithmap!{"a" => v}.auto_prune_with::<Epsilon<9>>())
+    ///     .collect();
+    /// assert_eq!(PrunedMap::<_, _, Epsilon<9>>::par_sum(&y), y.iter().sum());
+    /// ```
+    pub fn par_sum(maps: &[Self]) -> Self
+    where
+        K: Clone + Eq + Hash,
+        V: for<'x> AddAssign<&'x V> + Clone,
+    {
+        let shards = rayon::current_num_threads();
+        let shard = |k: &K| {
+            let mut hasher = DefaultHasher::new();
+            k.hash(&mut hasher);
+            (hasher.finish() % shards as u64) as usize
+        };
+        let buckets: Vec<_> = maps
+            .par_iter()
+            .map(|map| {
+                let mut buckets = vec![Vec::new(); shards];
+                for (i, (k, v)) in map.storage.iter().enumerate() {
+                    buckets[shard(k)].push((i, k, v));
+                }
+                buckets
+            })
+            .collect();
+        let mut sums: Vec<_> = (0..shards)
+            .into_par_iter()
+            .flat_map_iter(|i| {
+                let mut sums = HashMap::new();
+                let mut scratch = S::default();
+                for (m, buckets) in buckets.iter().enumerate() {
+                    for &(j, k, v2) in &buckets[i] {
+                        let (first, v) = match sums.remove(k) {
+                            Some((first, mut v1)) => {
+                                v1 += v2;
+                                (first, v1)
+                            }
+                            None => ((m, j), v2.clone()),
+                        };
+                        // The serial sum tidies after every map, which may drop the key.
+                        scratch.insert(k.clone(), v);
+                        scratch.tidy();
+                        if let Some(v) = scratch.remove(k) {
+                            sums.insert(k, (first, v));
+                        }
+                    }
+                }
+                sums.into_iter().map(|(k, (first, v))| (first, k, v))
+            })
+            .collect();
+        // Insert keys in the order the serial sum inserts them.
+        sums.par_sort_unstable_by_key(|&(first, _, _)| first);
+        let mut sum = Self::new();
+        for (_, k, v) in sums {
+            sum.storage.insert(k.clone(), v);
+        }
+        sum.storage.tidy();
+        sum
+    }
+
+    fn par_apply<F>(&mut self, f: F)
+    where
+        F: Fn(&mut V) + Sync,
+    {
+        (&mut self.storage).into_par_iter().for_each(|(_, v)| f(v));
+        self.storage.tidy();
+    }
+
+    /// Applies `op` to shared keys in parallel, then inserts `missing` of the rest of
+    /// `other` serially, in its iteration order.
+    fn par_merge<F, G>(&mut self, other: &Self, op: F, mut missing: G)
+    where
+        K: Clone,
+        F: Fn(&mut V, &V) + Sync,
+        G: FnMut(&V) -> V,
+    {
+        (&mut self.storage).into_par_iter().for_each(|(k, v1)| {
+            if let Some(v2) = other.storage.get(k) {
+                op(v1, v2);
+            }
+        });
+        for (k, v2) in other.storage.iter() {
+            if self.storage.get(k).is_none() {
+                self.storage.insert(k.clone(), missing(v2));
+            }
+        }
+        self.storage.tidy();
+    }
+}
+
+/// Lets parallel operations run on [`Sparse`] storage.
+///
+/// ```
+/// # use arith::*;
+/// let x = arithmap!{"a" => 1, "b" => 2}.sparse();
+///
+/// assert_eq!(x.par_sub(1), arithmap!{"b" => 1}.sparse());
+/// ```
+impl<'a, S, V> IntoParallelIterator for &'a mut Sparse<S, V>
+where
+    &'a mut S: IntoParallelIterator,
+{
+    type Iter = <&'a mut S as IntoParallelIterator>::Iter;
+    type Item = <&'a mut S as IntoParallelIterator>::Item;
+    fn into_par_iter(self) -> Self::Iter {
+        (&mut self.inner).into_par_iter()
+    }
+}
+
+/// Lets parallel operations run on [`Pruned`] storage.
+///
+/// ```
+/// # use arith::*;
+/// let x = arithmap!{"a" => 1, "b" => 2}.auto_prune();
+///
+/// assert_eq!(x.par_sub(1).len(), 1);
+/// ```
+impl<'a, S, P> IntoParallelIterator for &'a mut Pruned<S, P>
+where
+    &'a mut S: IntoParallelIterator,
+{
+    type Iter = <&'a mut S as IntoParallelIterator>::Iter;
+    type Item = <&'a mut S as IntoParallelIterator>::Item;
+    fn into_par_iter(self) -> Self::Iter {
+        (&mut self.inner).into_par_iter()
+    }
+}
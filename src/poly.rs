@@ -0,0 +1,549 @@
+//! Polynomials as maps from monomial to coefficient.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::ops::{Add, AddAssign, Sub, SubAssign, Mul, MulAssign, DivAssign, Neg};
+
+use crate::num::{One, Zero};
+use crate::prune::Pruned;
+use crate::{ArithMap, Storage};
+
+/// Exponents of the variables of a monomial: a single `u32` for univariate polynomials,
+/// or `[u32; N]` for `N` variables.
+pub trait Monomial: Clone + Ord {
+    /// Number of variables.
+    const VARIABLES: usize;
+
+    /// The monomial with every exponent zero.
+    fn constant() -> Self;
+
+    /// Product of two monomials, adding up their exponents.
+    fn mul(&self, other: &Self) -> Self;
+
+    /// Exponent of variable `var`; panics if there is no such variable.
+    fn exponent(&self, var: usize) -> u32;
+
+    /// Copy with the exponent of `var` replaced; panics if there is no such variable.
+    fn with_exponent(&self, var: usize, exponent: u32) -> Self;
+}
+
+impl Monomial for u32 {
+    const VARIABLES: usize = 1;
+
+    fn constant() -> Self {
+        0
+    }
+
+    fn mul(&self, other: &Self) -> Self {
+        self + other
+    }
+
+    fn exponent(&self, var: usize) -> u32 {
+        assert_eq!(var, 0, "univariate monomial");
+        *self
+    }
+
+    fn with_exponent(&self, var: usize, exponent: u32) -> Self {
+        assert_eq!(var, 0, "univariate monomial");
+        exponent
+    }
+}
+
+impl<const N: usize> Monomial for [u32; N] {
+    const VARIABLES: usize = N;
+
+    fn constant() -> Self {
+        [0; N]
+    }
+
+    fn mul(&self, other: &Self) -> Self {
+        let mut e = *self;
+        for (e1, e2) in e.iter_mut().zip(other) {
+            *e1 += e2;
+        }
+        e
+    }
+
+    fn exponent(&self, var: usize) -> u32 {
+        self[var]
+    }
+
+    fn with_exponent(&self, var: usize, exponent: u32) -> Self {
+        let mut e = *self;
+        e[var] = exponent;
+        e
+    }
+}
+
+/// A polynomial with coefficients `V`, keyed by monomial `M`.
+///
+/// Terms are kept in [`Pruned`] storage, so zero coefficients are dropped after every
+/// operation.  `+`, `-` and scalar `*` are those of [`ArithMap`]; `*` between polynomials
+/// multiplies them out.
+///
+/// ```
+/// # use arith::*;
+/// let x = Polynomial::from_coefficients(vec![1, 1]); // 1 + x
+/// let y = Polynomial::from_coefficients(vec![-1, 1]); // -1 + x
+///
+/// assert_eq!(x.clone() * y.clone(), Polynomial::from_coefficients(vec![-1, 0, 1]));
+/// assert_eq!(x.clone() - y.clone(), Polynomial::from_coefficients(vec![2]));
+/// assert_eq!((x + y) * 3, Polynomial::term(1, 6));
+/// ```
+///
+/// Multivariate polynomials are keyed by an array of exponents:
+///
+/// ```
+/// # use arith::*;
+/// let x = Polynomial::term([1, 0], 1);
+/// let y = Polynomial::term([0, 1], 1);
+/// let z = (x.clone() + y.clone()) * (x - y); // x^2 - y^2
+///
+/// assert_eq!(z.eval(&[3, 2]), 5);
+/// assert_eq!(z.derivative_by(1), Polynomial::term([0, 1], -2));
+/// ```
+pub struct Polynomial<M, V> {
+    pub terms: ArithMap<M, V, Pruned<BTreeMap<M, V>>>,
+}
+
+impl<M, V> Polynomial<M, V>
+where
+    M: Monomial,
+    V: Zero,
+{
+    /// The single term `coefficient * monomial`.
+    ///
+    /// ```
+    /// # use arith::*;
+    /// let x = Polynomial::term(2, 3.0);
+    ///
+    /// assert_eq!(x.eval(&[2.0]), 12.0);
+    /// assert!(Polynomial::term(2, 0.0).terms.is_empty());
+    /// ```
+    pub fn term(monomial: M, coefficient: V) -> Self {
+        Polynomial::from(ArithMap::from(BTreeMap::from([(monomial, coefficient)])))
+    }
+
+    /// Value at `point`, which holds one value per variable; panics if it holds any other
+    /// number of values.
+    ///
+    /// ```
+    /// # use arith::*;
+    /// let x = Polynomial::from_coefficients(vec![1, 0, 2]); // 1 + 2x^2
+    ///
+    /// assert_eq!(x.eval(&[3]), 19);
+    /// ```
+    ///
+    /// ```should_panic
+    /// # use arith::*;
+    /// Polynomial::term([1, 1], 2).eval(&[3]);
+    /// ```
+    pub fn eval(&self, point: &[V]) -> V
+    where
+        V: Clone + One + for<'x> AddAssign<&'x V> + for<'x> MulAssign<&'x V>,
+    {
+        assert_eq!(point.len(), M::VARIABLES, "one value per variable");
+        let mut value = V::zero();
+        for (m, c) in self.terms.storage.iter() {
+            let mut t = c.clone();
+            for (var, x) in point.iter().enumerate() {
+                t *= &pow(x, m.exponent(var));
+            }
+            value += &t;
+        }
+        value
+    }
+
+    /// Partial derivative with respect to variable `var`.
+    ///
+    /// ```
+    /// # use arith::*;
+    /// let x = Polynomial::term([2, 1], 3); // 3x^2y
+    ///
+    /// assert_eq!(x.derivative_by(0), Polynomial::term([1, 1], 6));
+    /// ```
+    pub fn derivative_by(&self, var: usize) -> Self
+    where
+        V: Clone + One + for<'x> AddAssign<&'x V> + for<'x> MulAssign<&'x V>,
+    {
+        let mut storage = Pruned::<BTreeMap<M, V>>::default();
+        for (m, c) in self.terms.storage.iter() {
+            let e = m.exponent(var);
+            if e > 0 {
+                let mut c = c.clone();
+                c *= &from_count(e);
+                storage.insert(m.with_exponent(var, e - 1), c);
+            }
+        }
+        Polynomial { terms: ArithMap::from(storage) }
+    }
+
+    /// Antiderivative with respect to variable `var`, with a zero constant of
+    /// integration; `None` if a coefficient doesn't divide exactly, as with integers.
+    ///
+    /// ```
+    /// # use arith::*;
+    /// let x = Polynomial::term([1, 1], 6.0); // 6xy
+    ///
+    /// assert_eq!(x.integral_by(1), Some(Polynomial::term([1, 2], 3.0)));
+    /// assert_eq!(Polynomial::term([1, 1], 3).integral_by(1), None);
+    /// ```
+    pub fn integral_by(&self, var: usize) -> Option<Self>
+    where
+        V: Clone + One + for<'x> AddAssign<&'x V> + SubAssign + for<'x> DivAssign<&'x V>,
+        for<'x> &'x V: Mul<Output = V>,
+    {
+        let mut storage = Pruned::<BTreeMap<M, V>>::default();
+        for (m, c) in self.terms.storage.iter() {
+            let e = m.exponent(var) + 1;
+            storage.insert(m.with_exponent(var, e), divide(c, &from_count(e))?);
+        }
+        Some(Polynomial { terms: ArithMap::from(storage) })
+    }
+
+    /// The product of `self` and `other`, multiplying out every pair of terms.
+    fn convolve(&self, other: &Self) -> Self
+    where
+        V: AddAssign,
+        for<'x> &'x V: Mul<Output = V>,
+    {
+        let mut terms: ArithMap<M, V, Pruned<BTreeMap<M, V>>> = ArithMap::new();
+        for (m1, c1) in self.terms.storage.iter() {
+            terms.extend(other.terms.storage.iter().map(|(m2, c2)| (m1.mul(m2), c1 * c2)));
+        }
+        Polynomial { terms }
+    }
+}
+
+impl<V> Polynomial<u32, V>
+where
+    V: Zero,
+{
+    /// Polynomial with the `i`-th coefficient on `x^i`.
+    ///
+    /// ```
+    /// # use arith::*;
+    /// let x = Polynomial::from_coefficients(vec![1, 0, 3]);
+    ///
+    /// assert_eq!(x, Polynomial::term(0, 1) + Polynomial::term(2, 3));
+    /// ```
+    pub fn from_coefficients<I>(coefficients: I) -> Self
+    where
+        I: IntoIterator<Item = V>,
+    {
+        let terms = (0..).zip(coefficients).collect::<BTreeMap<_, _>>();
+        Polynomial::from(ArithMap::from(terms))
+    }
+
+    /// Highest exponent, `None` for the zero polynomial.
+    ///
+    /// ```
+    /// # use arith::*;
+    /// assert_eq!(Polynomial::from_coefficients(vec![1, 2, 0]).degree(), Some(1));
+    /// assert_eq!(Polynomial::from_coefficients(vec![0]).degree(), None);
+    /// ```
+    pub fn degree(&self) -> Option<u32> {
+        self.terms.storage.inner.keys().next_back().copied()
+    }
+
+    /// ```
+    /// # use arith::*;
+    /// let x = Polynomial::from_coefficients(vec![5, 3, 2]); // 5 + 3x + 2x^2
+    ///
+    /// assert_eq!(x.derivative(), Polynomial::from_coefficients(vec![3, 4]));
+    /// ```
+    pub fn derivative(&self) -> Self
+    where
+        V: Clone + One + for<'x> AddAssign<&'x V> + for<'x> MulAssign<&'x V>,
+    {
+        self.derivative_by(0)
+    }
+
+    /// Antiderivative with a zero constant of integration; `None` if a coefficient doesn't
+    /// divide exactly.
+    ///
+    /// ```
+    /// # use arith::*;
+    /// let x = Polynomial::from_coefficients(vec![3.0, 4.0]);
+    ///
+    /// assert_eq!(x.integral(), Some(Polynomial::from_coefficients(vec![0.0, 3.0, 2.0])));
+    /// assert_eq!(Polynomial::from_coefficients(vec![2, 2]).integral().unwrap().derivative(), Polynomial::from_coefficients(vec![2, 2]));
+    /// assert_eq!(Polynomial::from_coefficients(vec![1, 1]).integral(), None);
+    /// ```
+    pub fn integral(&self) -> Option<Self>
+    where
+        V: Clone + One + for<'x> AddAssign<&'x V> + SubAssign + for<'x> DivAssign<&'x V>,
+        for<'x> &'x V: Mul<Output = V>,
+    {
+        self.integral_by(0)
+    }
+
+    /// `self(other(x))`.
+    ///
+    /// ```
+    /// # use arith::*;
+    /// let x = Polynomial::from_coefficients(vec![0, 0, 1]); // x^2
+    /// let y = Polynomial::from_coefficients(vec![1, 1]); // 1 + x
+    ///
+    /// assert_eq!(x.compose(&y), Polynomial::from_coefficients(vec![1, 2, 1]));
+    /// ```
+    pub fn compose(&self, other: &Self) -> Self
+    where
+        V: Clone + One + AddAssign + for<'x> MulAssign<&'x V>,
+        for<'x> &'x V: Mul<Output = V>,
+    {
+        let mut composition = Polynomial::default();
+        let mut power = Polynomial::term(0, V::one());
+        let mut e = 0;
+        for (&k, c) in self.terms.storage.iter() {
+            while e < k {
+                power = power.convolve(other);
+                e += 1;
+            }
+            composition += power.clone() * c.clone();
+        }
+        composition
+    }
+
+    /// Quotient and remainder of long division, `None` if `divisor` is zero.
+    ///
+    /// Leading coefficients are divided as `V` divides them.  With floats, the rounding error
+    /// left behind in each leading coefficient is dropped; with integers, division stops at
+    /// the first leading coefficient that doesn't divide exactly, leaving it in the
+    /// remainder.
+    ///
+    /// ```
+    /// # use arith::*;
+    /// let x = Polynomial::from_coefficients(vec![-1.0, 0.0, 0.0, 1.0]); // x^3 - 1
+    /// let y = Polynomial::from_coefficients(vec![-1.0, 1.0]); // x - 1
+    /// let (q, r) = x.div_rem(&y).unwrap();
+    ///
+    /// assert_eq!(q, Polynomial::from_coefficients(vec![1.0, 1.0, 1.0]));
+    /// assert_eq!(r, Polynomial::default());
+    /// assert!(x.div_rem(&Polynomial::default()).is_none());
+    ///
+    /// let x = Polynomial::from_coefficients(vec![1, 2, 3]); // 1 + 2x + 3x^2
+    /// let y = Polynomial::from_coefficients(vec![0, 2]); // 2x
+    ///
+    /// assert_eq!(x.div_rem(&y), Some((Polynomial::default(), x.clone())));
+    /// assert_eq!((x.clone() * 2).div_rem(&y), Some((Polynomial::from_coefficients(vec![2, 3]), Polynomial::term(0, 2))));
+    /// ```
+    pub fn div_rem(&self, divisor: &Self) -> Option<(Self, Self)>
+    where
+        V: Clone + AddAssign + SubAssign + for<'x> DivAssign<&'x V>,
+        for<'x> &'x V: Mul<Output = V>,
+    {
+        let (&dd, dc) = divisor.terms.storage.inner.iter().next_back()?;
+        let mut quotient = Polynomial::default();
+        let mut remainder = self.clone();
+        while let Some((&rd, rc)) = remainder.terms.storage.inner.iter().next_back() {
+            if rd < dd {
+                break;
+            }
+            let t = match divide(rc, dc) {
+                Some(t) if !t.is_zero() => t,
+                _ => break,
+            };
+            let term = Polynomial::term(rd - dd, t);
+            remainder -= term.convolve(divisor);
+            // Cancels exactly, or up to rounding error; drop whatever is left.
+            remainder.terms.storage.remove(&rd);
+            quotient += term;
+        }
+        Some((quotient, remainder))
+    }
+}
+
+/// `x / y`, or `None` if the division leaves a remainder, as with integers.  A residue that
+/// itself divides to something non-zero is rounding error, as with floats, and not a
+/// remainder.
+fn divide<V>(x: &V, y: &V) -> Option<V>
+where
+    V: Clone + Zero + SubAssign + for<'x> DivAssign<&'x V>,
+    for<'x> &'x V: Mul<Output = V>,
+{
+    let mut q = x.clone();
+    q /= y;
+    let mut residue = x.clone();
+    residue -= &q * y;
+    if residue.is_zero() {
+        return Some(q);
+    }
+    residue /= y;
+    if residue.is_zero() {
+        None
+    } else {
+        Some(q)
+    }
+}
+
+/// `x` to the power of `e`, by squaring.
+fn pow<V>(x: &V, mut e: u32) -> V
+where
+    V: Clone + One + for<'x> MulAssign<&'x V>,
+{
+    let mut base = x.clone();
+    let mut power = V::one();
+    while e > 0 {
+        if e & 1 == 1 {
+            power *= &base;
+        }
+        let b = base.clone();
+        base *= &b;
+        e >>= 1;
+    }
+    power
+}
+
+/// `n` as a value, by doubling and adding one.
+fn from_count<V>(n: u32) -> V
+where
+    V: Clone + Zero + One + for<'x> AddAssign<&'x V>,
+{
+    let mut value = V::zero();
+    for bit in (0..32).rev() {
+        let v = value.clone();
+        value += &v;
+        if n >> bit & 1 == 1 {
+            value += &V::one();
+        }
+    }
+    value
+}
+
+impl<M, V, S> From<ArithMap<M, V, S>> for Polynomial<M, V>
+where
+    M: Monomial,
+    V: Zero,
+    S: Storage<M, V>,
+{
+    fn from(map: ArithMap<M, V, S>) -> Self {
+        let terms = map.into_iter().collect::<BTreeMap<_, _>>();
+        Polynomial { terms: ArithMap::from(terms).auto_prune() }
+    }
+}
+
+impl<M, V> Default for Polynomial<M, V>
+where
+    M: Ord,
+{
+    fn default() -> Self {
+        Polynomial { terms: Default::default() }
+    }
+}
+
+impl<M, V> Clone for Polynomial<M, V>
+where
+    M: Clone,
+    V: Clone,
+{
+    fn clone(&self) -> Self {
+        Polynomial { terms: self.terms.clone() }
+    }
+}
+
+impl<M, V> PartialEq for Polynomial<M, V>
+where
+    M: PartialEq,
+    V: PartialEq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.terms == other.terms
+    }
+}
+
+impl<M, V> Eq for Polynomial<M, V>
+where
+    M: Eq,
+    V: Eq,
+{
+}
+
+impl<M, V> fmt::Debug for Polynomial<M, V>
+where
+    M: fmt::Debug,
+    V: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.terms.fmt(f)
+    }
+}
+
+impl<M, V> Add for Polynomial<M, V>
+where
+    M: Monomial,
+    V: AddAssign + Zero,
+{
+    type Output = Self;
+    fn add(mut self, other: Self) -> Self {
+        self += other;
+        self
+    }
+}
+
+impl<M, V> AddAssign for Polynomial<M, V>
+where
+    M: Monomial,
+    V: AddAssign + Zero,
+{
+    fn add_assign(&mut self, other: Self) {
+        self.terms += other.terms;
+    }
+}
+
+impl<M, V> Sub for Polynomial<M, V>
+where
+    M: Monomial,
+    V: SubAssign + Zero,
+{
+    type Output = Self;
+    fn sub(mut self, other: Self) -> Self {
+        self -= other;
+        self
+    }
+}
+
+impl<M, V> SubAssign for Polynomial<M, V>
+where
+    M: Monomial,
+    V: SubAssign + Zero,
+{
+    fn sub_assign(&mut self, other: Self) {
+        self.terms -= other.terms;
+    }
+}
+
+impl<M, V> Mul<V> for Polynomial<M, V>
+where
+    M: Monomial,
+    V: for<'x> MulAssign<&'x V> + Zero,
+{
+    type Output = Self;
+    fn mul(mut self, other: V) -> Self {
+        self.terms *= other;
+        self
+    }
+}
+
+impl<M, V> Mul for Polynomial<M, V>
+where
+    M: Monomial,
+    V: AddAssign + Zero,
+    for<'x> &'x V: Mul<Output = V>,
+{
+    type Output = Self;
+    fn mul(self, other: Self) -> Self {
+        self.convolve(&other)
+    }
+}
+
+impl<M, V> Neg for Polynomial<M, V>
+where
+    M: Monomial,
+    V: Neg<Output = V> + Zero,
+{
+    type Output = Self;
+    fn neg(self) -> Self {
+        Polynomial { terms: -self.terms }
+    }
+}
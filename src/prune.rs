@@ -0,0 +1,241 @@
+//! Automatic removal of zero entries after every operation.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::marker::PhantomData;
+
+use crate::num::{ToF64, Zero};
+use crate::{ArithMap, Storage};
+
+/// Decides which values [`Pruned`] storage drops.
+///
+/// Implement it on your own marker type for a custom predicate:
+///
+/// ```
+/// # use arith::*;
+/// #[derive(Default)]
+/// struct Negative;
+///
+/// impl PrunePolicy<i32> for Negative {
+///     fn is_zero(value: &i32) -> bool {
+///         *value <= 0
+///     }
+/// }
+///
+/// let x = arithmap!{"a" => 1, "b" => 2}.auto_prune_with::<Negative>();
+///
+/// assert_eq!(x - 1, arithmap!{"b" => 1}.auto_prune_with::<Negative>());
+/// ```
+pub trait PrunePolicy<V>: Default {
+    fn is_zero(value: &V) -> bool;
+}
+
+/// Drops values that are exactly [`Zero`].
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct Exact;
+
+impl<V> PrunePolicy<V> for Exact
+where
+    V: Zero,
+{
+    fn is_zero(value: &V) -> bool {
+        value.is_zero()
+    }
+}
+
+/// Drops values smaller than `10^-DIGITS` in absolute value.
+///
+/// ```
+/// # use arith::*;
+/// let x = arithmap!{"a" => 0.1, "b" => 0.3}.auto_prune_with::<Epsilon<9>>();
+///
+/// assert_eq!((x - 0.1).len(), 1);
+/// ```
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct Epsilon<const DIGITS: u32>;
+
+impl<V, const DIGITS: u32> PrunePolicy<V> for Epsilon<DIGITS>
+where
+    V: ToF64,
+{
+    fn is_zero(value: &V) -> bool {
+        value.to_f64().abs() < 10f64.powi(-(DIGITS as i32))
+    }
+}
+
+/// Storage adapter that removes zero entries, as decided by the policy `P`, after every
+/// operation.
+///
+/// ```
+/// # use arith::*;
+/// let x = arithmap!{"a" => 1, "b" => 2}.auto_prune();
+/// let y = arithmap!{"b" => 2, "c" => 3}.auto_prune();
+///
+/// assert_eq!(x - y, arithmap!{"a" => 1, "c" => -3}.auto_prune());
+/// ```
+pub struct Pruned<S, P = Exact> {
+    pub inner: S,
+    _policy: PhantomData<P>,
+}
+
+/// [`ArithMap`] over a `HashMap` with [`Pruned`] storage.
+pub type PrunedMap<K, V, P = Exact> = ArithMap<K, V, Pruned<HashMap<K, V>, P>>;
+
+impl<S, P> Pruned<S, P> {
+    /// ```
+    /// # use arith::*;
+    /// let x = arithmap!{"a" => 1}.auto_prune();
+    ///
+    /// assert_eq!(x.into_storage().into_inner()["a"], 1);
+    /// ```
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+}
+
+impl<S, P> From<S> for Pruned<S, P> {
+    fn from(inner: S) -> Self {
+        Pruned { inner, _policy: PhantomData }
+    }
+}
+
+impl<S, P> Default for Pruned<S, P>
+where
+    S: Default,
+{
+    fn default() -> Self {
+        Pruned::from(S::default())
+    }
+}
+
+impl<S, P> Clone for Pruned<S, P>
+where
+    S: Clone,
+{
+    fn clone(&self) -> Self {
+        Pruned::from(self.inner.clone())
+    }
+}
+
+impl<S, P> PartialEq for Pruned<S, P>
+where
+    S: PartialEq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.inner == other.inner
+    }
+}
+
+impl<S, P> Eq for Pruned<S, P> where S: Eq {}
+
+impl<S, P> fmt::Debug for Pruned<S, P>
+where
+    S: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.inner.fmt(f)
+    }
+}
+
+impl<S, P> IntoIterator for Pruned<S, P>
+where
+    S: IntoIterator,
+{
+    type Item = S::Item;
+    type IntoIter = S::IntoIter;
+    fn into_iter(self) -> Self::IntoIter {
+        self.inner.into_iter()
+    }
+}
+
+impl<K, V, S, P> Storage<K, V> for Pruned<S, P>
+where
+    S: Storage<K, V>,
+    P: PrunePolicy<V>,
+{
+    type Iter<'a>
+        = S::Iter<'a>
+    where
+        Self: 'a,
+        K: 'a,
+        V: 'a;
+
+    type IterMut<'a>
+        = S::IterMut<'a>
+    where
+        Self: 'a,
+        K: 'a,
+        V: 'a;
+
+    fn insert(&mut self, key: K, value: V) -> Option<V> {
+        self.inner.insert(key, value)
+    }
+
+    fn get(&self, key: &K) -> Option<&V> {
+        self.inner.get(key)
+    }
+
+    fn get_mut(&mut self, key: &K) -> Option<&mut V> {
+        self.inner.get_mut(key)
+    }
+
+    fn remove(&mut self, key: &K) -> Option<V> {
+        self.inner.remove(key)
+    }
+
+    fn iter(&self) -> Self::Iter<'_> {
+        self.inner.iter()
+    }
+
+    fn iter_mut(&mut self) -> Self::IterMut<'_> {
+        self.inner.iter_mut()
+    }
+
+    fn retain<F>(&mut self, f: F)
+    where
+        F: FnMut(&K, &mut V) -> bool,
+    {
+        self.inner.retain(f)
+    }
+
+    fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    fn tidy(&mut self) {
+        self.inner.tidy();
+        self.inner.retain(|_, v| !P::is_zero(v));
+    }
+}
+
+impl<K, V, S> ArithMap<K, V, S>
+where
+    S: Storage<K, V>,
+{
+    /// ```
+    /// # use arith::*;
+    /// let mut x = arithmap!{"a" => 1, "b" => 2}.auto_prune();
+    /// x -= 1;
+    ///
+    /// assert_eq!(x.len(), 1);
+    /// ```
+    pub fn auto_prune(self) -> ArithMap<K, V, Pruned<S>>
+    where
+        V: Zero,
+    {
+        self.auto_prune_with()
+    }
+
+    /// ```
+    /// # use arith::*;
+    /// let x = arithmap!{"a" => 1e-12, "b" => 1.0}.auto_prune_with::<Epsilon<6>>();
+    ///
+    /// assert_eq!(x.len(), 1);
+    /// ```
+    pub fn auto_prune_with<P>(self) -> ArithMap<K, V, Pruned<S, P>>
+    where
+        P: PrunePolicy<V>,
+    {
+        ArithMap::from(Pruned::from(self.storage))
+    }
+}
@@ -0,0 +1,90 @@
+//! `Serialize` and `Deserialize` for [`ArithMap`], behind the `serde` feature.
+
+use std::fmt;
+use std::marker::PhantomData;
+
+use serde::de::{Deserialize, Deserializer, MapAccess, Visitor};
+use serde::ser::{Serialize, SerializeMap, Serializer};
+
+use crate::{ArithMap, Storage};
+
+/// Serialised as a plain map.
+///
+/// ```
+/// # use arith::*;
+/// let x = arithbtreemap!{"a" => 1, "b" => 2};
+///
+/// assert_eq!(serde_json::to_string(&x).unwrap(), r#"{"a":1,"b":2}"#);
+/// ```
+impl<K, V, S> Serialize for ArithMap<K, V, S>
+where
+    S: Storage<K, V>,
+    K: Serialize,
+    V: Serialize,
+{
+    fn serialize<T>(&self, serializer: T) -> Result<T::Ok, T::Error>
+    where
+        T: Serializer,
+    {
+        let mut map = serializer.serialize_map(Some(self.storage.len()))?;
+        for (k, v) in self.storage.iter() {
+            map.serialize_entry(k, v)?;
+        }
+        map.end()
+    }
+}
+
+/// Deserialised from a plain map.  String keys can borrow from the input.
+///
+/// ```
+/// # use arith::*;
+/// let json = String::from(r#"{"a": 1.5, "b": 2}"#);
+/// let x: ArithMap<&str, f64> = serde_json::from_str(&json).unwrap();
+/// let y: ArithMap<String, f64> = serde_json::from_str(&json).unwrap();
+/// let z: ArithMap<String, f64> = serde_json::from_str(&serde_json::to_string(&y).unwrap()).unwrap();
+///
+/// assert_eq!(x, arithmap!{"a" => 1.5, "b" => 2.0});
+/// assert_eq!(y, z);
+///
+/// let p: PrunedMap<&str, i32> = serde_json::from_str(r#"{"a": 0, "b": 1}"#).unwrap();
+/// assert_eq!(p.len(), 1);
+/// ```
+impl<'de, K, V, S> Deserialize<'de> for ArithMap<K, V, S>
+where
+    S: Storage<K, V>,
+    K: Deserialize<'de>,
+    V: Deserialize<'de>,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_map(ArithMapVisitor(PhantomData))
+    }
+}
+
+struct ArithMapVisitor<K, V, S>(PhantomData<ArithMap<K, V, S>>);
+
+impl<'de, K, V, S> Visitor<'de> for ArithMapVisitor<K, V, S>
+where
+    S: Storage<K, V>,
+    K: Deserialize<'de>,
+    V: Deserialize<'de>,
+{
+    type Value = ArithMap<K, V, S>;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a map")
+    }
+
+    fn visit_map<A>(self, mut access: A) -> Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        let mut storage = S::default();
+        while let Some((k, v)) = access.next_entry()? {
+            storage.insert(k, v);
+        }
+        Ok(ArithMap::from(storage))
+    }
+}
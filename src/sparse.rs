@@ -0,0 +1,190 @@
+//! Sparse-vector semantics, where a missing key and an explicit zero are the same thing.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::iter::Filter;
+use std::ops::Index;
+
+use crate::num::Zero;
+use crate::{ArithMap, Storage};
+
+/// Storage adapter that treats zero values as absent.
+///
+/// Equality ignores explicit zeros, indexing a missing key returns zero, and `len` and `iter`
+/// see only non-zero entries.  `iter_mut` still visits stored zeros, and so do the scalar
+/// operators built on it, serial and parallel alike.
+///
+/// ```
+/// # use arith::*;
+/// let x = arithmap!{"a" => 1, "b" => 2}.sparse();
+/// let y = arithmap!{"a" => 1, "b" => 2, "c" => 0}.sparse();
+///
+/// assert_eq!(x, y);
+/// assert_eq!(x[&"c"], 0);
+/// assert_eq!(y.len(), 2);
+/// assert_eq!(y.clone() + 1, arithmap!{"a" => 2, "b" => 3, "c" => 1}.sparse());
+/// assert!((x - y).is_empty());
+/// ```
+#[derive(Clone)]
+pub struct Sparse<S, V> {
+    pub inner: S,
+    zero: V,
+}
+
+/// [`ArithMap`] over a `HashMap` with [`Sparse`] semantics.
+pub type SparseMap<K, V> = ArithMap<K, V, Sparse<HashMap<K, V>, V>>;
+
+impl<S, V> Sparse<S, V> {
+    /// ```
+    /// # use arith::*;
+    /// let x = arithmap!{"a" => 0}.sparse();
+    ///
+    /// assert_eq!(x.into_storage().into_inner()["a"], 0);
+    /// ```
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+}
+
+impl<S, V> From<S> for Sparse<S, V>
+where
+    V: Zero,
+{
+    fn from(inner: S) -> Self {
+        Sparse { inner, zero: V::zero() }
+    }
+}
+
+impl<S, V> Default for Sparse<S, V>
+where
+    S: Default,
+    V: Zero,
+{
+    fn default() -> Self {
+        Sparse::from(S::default())
+    }
+}
+
+impl<S, V> fmt::Debug for Sparse<S, V>
+where
+    S: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.inner.fmt(f)
+    }
+}
+
+impl<S, V> IntoIterator for Sparse<S, V>
+where
+    S: IntoIterator,
+{
+    type Item = S::Item;
+    type IntoIter = S::IntoIter;
+    fn into_iter(self) -> Self::IntoIter {
+        self.inner.into_iter()
+    }
+}
+
+impl<K, V, S> Storage<K, V> for Sparse<S, V>
+where
+    S: Storage<K, V>,
+    V: Zero,
+{
+    type Iter<'a>
+        = Filter<S::Iter<'a>, fn(&(&'a K, &'a V)) -> bool>
+    where
+        Self: 'a,
+        K: 'a,
+        V: 'a;
+
+    type IterMut<'a>
+        = S::IterMut<'a>
+    where
+        Self: 'a,
+        K: 'a,
+        V: 'a;
+
+    fn insert(&mut self, key: K, value: V) -> Option<V> {
+        self.inner.insert(key, value)
+    }
+
+    fn get(&self, key: &K) -> Option<&V> {
+        self.inner.get(key)
+    }
+
+    fn get_mut(&mut self, key: &K) -> Option<&mut V> {
+        self.inner.get_mut(key)
+    }
+
+    fn remove(&mut self, key: &K) -> Option<V> {
+        self.inner.remove(key)
+    }
+
+    fn iter(&self) -> Self::Iter<'_> {
+        self.inner.iter().filter(|(_, v)| !v.is_zero())
+    }
+
+    fn iter_mut(&mut self) -> Self::IterMut<'_> {
+        self.inner.iter_mut()
+    }
+
+    fn retain<F>(&mut self, f: F)
+    where
+        F: FnMut(&K, &mut V) -> bool,
+    {
+        self.inner.retain(f)
+    }
+
+    /// Number of non-zero entries.
+    fn len(&self) -> usize {
+        self.iter().count()
+    }
+
+    fn tidy(&mut self) {
+        self.inner.tidy()
+    }
+}
+
+impl<K, V, S> ArithMap<K, V, S>
+where
+    S: Storage<K, V>,
+    V: Zero,
+{
+    /// ```
+    /// # use arith::*;
+    /// let x = arithmap!{"a" => 1, "b" => 0}.sparse();
+    ///
+    /// assert_eq!(x, arithmap!{"a" => 1}.sparse());
+    /// ```
+    pub fn sparse(self) -> ArithMap<K, V, Sparse<S, V>> {
+        ArithMap::from(Sparse::from(self.storage))
+    }
+}
+
+impl<K, V, S> PartialEq for ArithMap<K, V, Sparse<S, V>>
+where
+    S: Storage<K, V>,
+    V: Zero + PartialEq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.storage.iter().all(|(k, v)| *v == other[k])
+            && other.storage.iter().all(|(k, v)| *v == self[k])
+    }
+}
+
+impl<K, V, S> Eq for ArithMap<K, V, Sparse<S, V>>
+where
+    S: Storage<K, V>,
+    V: Zero + Eq,
+{
+}
+
+impl<K, V, S> Index<&K> for ArithMap<K, V, Sparse<S, V>>
+where
+    S: Storage<K, V>,
+{
+    type Output = V;
+    fn index(&self, key: &K) -> &V {
+        self.storage.inner.get(key).unwrap_or(&self.storage.zero)
+    }
+}
@@ -0,0 +1,335 @@
+//! Statistical summaries of map values.
+//!
+//! Sums are taken over values in sorted order with compensated summation, so a result
+//! depends only on the values and not on the map's iteration order.
+//!
+//! Explicit zeros are values like any other, except on [`Sparse`](crate::Sparse) storage,
+//! where they are absent keys and so take no part:
+//!
+//! ```
+//! # use arith::*;
+//! let x = arithmap!{"a" => 1, "b" => 3}.sparse();
+//! let y = arithmap!{"a" => 1, "b" => 3, "c" => 0}.sparse();
+//!
+//! assert_eq!(x.variance(), y.variance());
+//! assert_eq!(arithmap!{"a" => 1, "b" => 3, "c" => 0}.mean(), Some(4.0 / 3.0));
+//! ```
+
+use std::cmp::Ordering;
+
+use crate::num::ToF64;
+use crate::{ArithMap, Storage};
+
+impl<K, V, S> ArithMap<K, V, S>
+where
+    S: Storage<K, V>,
+{
+    /// Sum of the values as `f64`; unlike [`ArithMap::total`] it does not depend on
+    /// iteration order.
+    ///
+    /// ```
+    /// # use arith::*;
+    /// let x = arithmap!{"a" => 1e16, "b" => 1.0, "c" => -1e16};
+    ///
+    /// assert_eq!(x.sum(), 1.0);
+    /// ```
+    pub fn sum(&self) -> f64
+    where
+        V: ToF64,
+    {
+        sum(self.floats())
+    }
+
+    /// `None` for an empty map.
+    ///
+    /// ```
+    /// # use arith::*;
+    /// let x = arithmap!{"a" => 1, "b" => 2, "c" => 6};
+    ///
+    /// assert_eq!(x.mean(), Some(3.0));
+    /// ```
+    pub fn mean(&self) -> Option<f64>
+    where
+        V: ToF64,
+    {
+        mean(&self.floats())
+    }
+
+    /// Population variance; `None` for an empty map.
+    ///
+    /// ```
+    /// # use arith::*;
+    /// let x = arithmap!{"a" => 1, "b" => 3};
+    ///
+    /// assert_eq!(x.variance(), Some(1.0));
+    /// ```
+    pub fn variance(&self) -> Option<f64>
+    where
+        V: ToF64,
+    {
+        let xs = self.floats();
+        let n = xs.len() as f64;
+        let mean = mean(&xs)?;
+        let squares = xs.into_iter().map(|x| (x - mean).powi(2)).collect();
+        Some(sum(squares) / n)
+    }
+
+    /// Population standard deviation; `None` for an empty map.
+    ///
+    /// ```
+    /// # use arith::*;
+    /// let x = arithmap!{"a" => 1, "b" => 5};
+    ///
+    /// assert_eq!(x.std_dev(), Some(2.0));
+    /// ```
+    pub fn std_dev(&self) -> Option<f64>
+    where
+        V: ToF64,
+    {
+        self.variance().map(f64::sqrt)
+    }
+
+    /// Mean of the two middle values for an even number of values; `None` for an empty map.
+    ///
+    /// ```
+    /// # use arith::*;
+    /// assert_eq!(arithmap!{"a" => 3, "b" => 1, "c" => 2}.median(), Some(2.0));
+    /// assert_eq!(arithmap!{"a" => 4, "b" => 1, "c" => 2, "d" => 3}.median(), Some(2.5));
+    /// ```
+    pub fn median(&self) -> Option<f64>
+    where
+        V: ToF64,
+    {
+        let mut xs = self.floats();
+        xs.sort_by(f64::total_cmp);
+        let n = xs.len();
+        match n {
+            0 => None,
+            _ if n % 2 == 1 => Some(xs[n / 2]),
+            _ => Some((xs[n / 2 - 1] + xs[n / 2]) / 2.0),
+        }
+    }
+
+    /// ```
+    /// # use arith::*;
+    /// let x = arithmap!{"a" => 3, "b" => 1, "c" => 2};
+    ///
+    /// assert_eq!(x.min(), Some(&1));
+    /// ```
+    pub fn min(&self) -> Option<&V>
+    where
+        K: Ord,
+        V: PartialOrd,
+    {
+        self.argmin().and_then(|k| self.storage.get(k))
+    }
+
+    /// ```
+    /// # use arith::*;
+    /// let x = arithmap!{"a" => 3, "b" => 1, "c" => 2};
+    ///
+    /// assert_eq!(x.max(), Some(&3));
+    /// ```
+    pub fn max(&self) -> Option<&V>
+    where
+        K: Ord,
+        V: PartialOrd,
+    {
+        self.argmax().and_then(|k| self.storage.get(k))
+    }
+
+    /// Key of the smallest value, the smallest such key on ties; NaNs are skipped.
+    ///
+    /// ```
+    /// # use arith::*;
+    /// let x = arithmap!{"a" => 3, "b" => 1, "c" => 1};
+    /// let y = arithmap!{"a" => f64::NAN, "b" => 2.0, "c" => 1.0};
+    ///
+    /// assert_eq!(x.argmin(), Some(&"b"));
+    /// assert_eq!(y.argmin(), Some(&"c"));
+    /// assert_eq!(y.max(), Some(&2.0));
+    /// ```
+    pub fn argmin(&self) -> Option<&K>
+    where
+        K: Ord,
+        V: PartialOrd,
+    {
+        self.arg_extreme(Ordering::Less)
+    }
+
+    /// Key of the largest value, the smallest such key on ties; NaNs are skipped.
+    ///
+    /// ```
+    /// # use arith::*;
+    /// let x = arithmap!{"a" => 1, "b" => 3, "c" => 3};
+    ///
+    /// assert_eq!(x.argmax(), Some(&"b"));
+    /// ```
+    pub fn argmax(&self) -> Option<&K>
+    where
+        K: Ord,
+        V: PartialOrd,
+    {
+        self.arg_extreme(Ordering::Greater)
+    }
+
+    /// Mean of the keys weighted by the values, for a histogram of value to count; `None`
+    /// if the weights sum to zero.
+    ///
+    /// ```
+    /// # use arith::*;
+    /// let x = arithmap!{1 => 3, 5 => 1};
+    ///
+    /// assert_eq!(x.weighted_mean(), Some(2.0));
+    /// ```
+    pub fn weighted_mean(&self) -> Option<f64>
+    where
+        K: ToF64,
+        V: ToF64,
+    {
+        let total = self.sum();
+        if total == 0.0 {
+            return None;
+        }
+        let products = self.weighted().into_iter().map(|(x, w)| x * w).collect();
+        Some(sum(products) / total)
+    }
+
+    /// Population variance of the keys weighted by the values; `None` if the weights sum
+    /// to zero.
+    ///
+    /// ```
+    /// # use arith::*;
+    /// let x = arithmap!{1 => 3, 5 => 1};
+    ///
+    /// assert_eq!(x.weighted_variance(), Some(3.0));
+    /// ```
+    pub fn weighted_variance(&self) -> Option<f64>
+    where
+        K: ToF64,
+        V: ToF64,
+    {
+        let mean = self.weighted_mean()?;
+        let squares = self
+            .weighted()
+            .into_iter()
+            .map(|(x, w)| (x - mean).powi(2) * w)
+            .collect();
+        Some(sum(squares) / self.sum())
+    }
+
+    /// ```
+    /// # use arith::*;
+    /// let x = arithmap!{1 => 3, 5 => 1};
+    ///
+    /// assert_eq!(x.weighted_std_dev(), Some(3f64.sqrt()));
+    /// ```
+    pub fn weighted_std_dev(&self) -> Option<f64>
+    where
+        K: ToF64,
+        V: ToF64,
+    {
+        self.weighted_variance().map(f64::sqrt)
+    }
+
+    /// Median of the keys weighted by the values; when exactly half the weight lies on
+    /// either side, the mean of the two keys around the middle.  `None` if the weights sum
+    /// to zero.
+    ///
+    /// ```
+    /// # use arith::*;
+    /// assert_eq!(arithmap!{1 => 3, 5 => 1}.weighted_median(), Some(1.0));
+    /// assert_eq!(arithmap!{1 => 2, 5 => 2}.weighted_median(), Some(3.0));
+    /// ```
+    pub fn weighted_median(&self) -> Option<f64>
+    where
+        K: ToF64,
+        V: ToF64,
+    {
+        let half = self.sum() / 2.0;
+        if half == 0.0 {
+            return None;
+        }
+        let mut pairs = self.weighted();
+        pairs.retain(|&(_, w)| w > 0.0);
+        pairs.sort_by(|(x1, _), (x2, _)| x1.total_cmp(x2));
+        let mut cumulative = 0.0;
+        for (i, &(x, w)) in pairs.iter().enumerate() {
+            cumulative += w;
+            if cumulative == half {
+                return Some(pairs.get(i + 1).map_or(x, |&(next, _)| (x + next) / 2.0));
+            }
+            if cumulative > half {
+                return Some(x);
+            }
+        }
+        pairs.last().map(|&(x, _)| x)
+    }
+
+    fn floats(&self) -> Vec<f64>
+    where
+        V: ToF64,
+    {
+        self.storage.iter().map(|(_, v)| v.to_f64()).collect()
+    }
+
+    fn weighted(&self) -> Vec<(f64, f64)>
+    where
+        K: ToF64,
+        V: ToF64,
+    {
+        self.storage
+            .iter()
+            .map(|(k, v)| (k.to_f64(), v.to_f64()))
+            .collect()
+    }
+
+    /// Key whose value compares as `ordering` to every other, preferring smaller keys and
+    /// skipping values that don't compare to themselves, like NaN.
+    fn arg_extreme(&self, ordering: Ordering) -> Option<&K>
+    where
+        K: Ord,
+        V: PartialOrd,
+    {
+        let mut best: Option<(&K, &V)> = None;
+        for (k, v) in self.storage.iter() {
+            if v.partial_cmp(v).is_none() {
+                continue;
+            }
+            best = match best {
+                Some((bk, bv)) => match v.partial_cmp(bv) {
+                    Some(o) if o == ordering => Some((k, v)),
+                    Some(Ordering::Equal) if k < bk => Some((k, v)),
+                    _ => Some((bk, bv)),
+                },
+                None => Some((k, v)),
+            };
+        }
+        best.map(|(k, _)| k)
+    }
+}
+
+/// `None` for no values.
+fn mean(xs: &[f64]) -> Option<f64> {
+    if xs.is_empty() {
+        return None;
+    }
+    Some(sum(xs.to_vec()) / xs.len() as f64)
+}
+
+/// Neumaier summation over the values sorted in ascending order.
+fn sum(mut xs: Vec<f64>) -> f64 {
+    xs.sort_by(f64::total_cmp);
+    let mut sum = 0.0;
+    let mut compensation = 0.0;
+    for x in xs {
+        let t = sum + x;
+        if f64::abs(sum) >= f64::abs(x) {
+            compensation += (sum - t) + x;
+        } else {
+            compensation += (x - t) + sum;
+        }
+        sum = t;
+    }
+    sum + compensation
+}
@@ -0,0 +1,226 @@
+//! Backends that an [`ArithMap`](crate::ArithMap) can keep its values in.
+
+use std::collections::{btree_map, hash_map, BTreeMap, HashMap};
+use std::hash::{BuildHasher, Hash};
+
+/// Minimal map interface the arithmetic is implemented against.
+///
+/// Implement it for your own map type to get every operator of
+/// [`ArithMap`](crate::ArithMap) for free.  `HashMap` with any hasher and `BTreeMap` are
+/// supported out of the box, and `IndexMap` with the `indexmap` feature.
+///
+/// ```
+/// # use arith::*;
+/// # use std::collections::hash_map::DefaultHasher;
+/// # use std::collections::HashMap;
+/// # use std::hash::BuildHasherDefault;
+/// type Hasher = BuildHasherDefault<DefaultHasher>;
+/// let x: HashMap<_, _, Hasher> = vec![("a", 1), ("b", 2)].into_iter().collect();
+/// let y: HashMap<_, _, Hasher> = vec![("b", 2), ("c", 3)].into_iter().collect();
+/// let z = ArithMap::from(x) + ArithMap::from(y);
+///
+/// assert_eq!(z.storage[&"b"], 4);
+/// ```
+pub trait Storage<K, V>: Default + IntoIterator<Item = (K, V)> {
+    type Iter<'a>: Iterator<Item = (&'a K, &'a V)>
+    where
+        Self: 'a,
+        K: 'a,
+        V: 'a;
+
+    type IterMut<'a>: Iterator<Item = (&'a K, &'a mut V)>
+    where
+        Self: 'a,
+        K: 'a,
+        V: 'a;
+
+    fn insert(&mut self, key: K, value: V) -> Option<V>;
+
+    fn get(&self, key: &K) -> Option<&V>;
+
+    fn get_mut(&mut self, key: &K) -> Option<&mut V>;
+
+    fn remove(&mut self, key: &K) -> Option<V>;
+
+    fn iter(&self) -> Self::Iter<'_>;
+
+    fn iter_mut(&mut self) -> Self::IterMut<'_>;
+
+    fn retain<F>(&mut self, f: F)
+    where
+        F: FnMut(&K, &mut V) -> bool;
+
+    fn len(&self) -> usize;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Called at the end of every arithmetic operation, for adapters that keep an invariant.
+    fn tidy(&mut self) {}
+}
+
+impl<K, V, H> Storage<K, V> for HashMap<K, V, H>
+where
+    K: Hash + Eq,
+    H: BuildHasher + Default,
+{
+    type Iter<'a>
+        = hash_map::Iter<'a, K, V>
+    where
+        Self: 'a,
+        K: 'a,
+        V: 'a;
+
+    type IterMut<'a>
+        = hash_map::IterMut<'a, K, V>
+    where
+        Self: 'a,
+        K: 'a,
+        V: 'a;
+
+    fn insert(&mut self, key: K, value: V) -> Option<V> {
+        HashMap::insert(self, key, value)
+    }
+
+    fn get(&self, key: &K) -> Option<&V> {
+        HashMap::get(self, key)
+    }
+
+    fn get_mut(&mut self, key: &K) -> Option<&mut V> {
+        HashMap::get_mut(self, key)
+    }
+
+    fn remove(&mut self, key: &K) -> Option<V> {
+        HashMap::remove(self, key)
+    }
+
+    fn iter(&self) -> Self::Iter<'_> {
+        HashMap::iter(self)
+    }
+
+    fn iter_mut(&mut self) -> Self::IterMut<'_> {
+        HashMap::iter_mut(self)
+    }
+
+    fn retain<F>(&mut self, f: F)
+    where
+        F: FnMut(&K, &mut V) -> bool,
+    {
+        HashMap::retain(self, f)
+    }
+
+    fn len(&self) -> usize {
+        HashMap::len(self)
+    }
+}
+
+impl<K, V> Storage<K, V> for BTreeMap<K, V>
+where
+    K: Ord,
+{
+    type Iter<'a>
+        = btree_map::Iter<'a, K, V>
+    where
+        Self: 'a,
+        K: 'a,
+        V: 'a;
+
+    type IterMut<'a>
+        = btree_map::IterMut<'a, K, V>
+    where
+        Self: 'a,
+        K: 'a,
+        V: 'a;
+
+    fn insert(&mut self, key: K, value: V) -> Option<V> {
+        BTreeMap::insert(self, key, value)
+    }
+
+    fn get(&self, key: &K) -> Option<&V> {
+        BTreeMap::get(self, key)
+    }
+
+    fn get_mut(&mut self, key: &K) -> Option<&mut V> {
+        BTreeMap::get_mut(self, key)
+    }
+
+    fn remove(&mut self, key: &K) -> Option<V> {
+        BTreeMap::remove(self, key)
+    }
+
+    fn iter(&self) -> Self::Iter<'_> {
+        BTreeMap::iter(self)
+    }
+
+    fn iter_mut(&mut self) -> Self::IterMut<'_> {
+        BTreeMap::iter_mut(self)
+    }
+
+    fn retain<F>(&mut self, f: F)
+    where
+        F: FnMut(&K, &mut V) -> bool,
+    {
+        BTreeMap::retain(self, f)
+    }
+
+    fn len(&self) -> usize {
+        BTreeMap::len(self)
+    }
+}
+
+#[cfg(feature = "indexmap")]
+impl<K, V, H> Storage<K, V> for indexmap::IndexMap<K, V, H>
+where
+    K: Hash + Eq,
+    H: BuildHasher + Default,
+{
+    type Iter<'a>
+        = indexmap::map::Iter<'a, K, V>
+    where
+        Self: 'a,
+        K: 'a,
+        V: 'a;
+
+    type IterMut<'a>
+        = indexmap::map::IterMut<'a, K, V>
+    where
+        Self: 'a,
+        K: 'a,
+        V: 'a;
+
+    fn insert(&mut self, key: K, value: V) -> Option<V> {
+        indexmap::IndexMap::insert(self, key, value)
+    }
+
+    fn get(&self, key: &K) -> Option<&V> {
+        indexmap::IndexMap::get(self, key)
+    }
+
+    fn get_mut(&mut self, key: &K) -> Option<&mut V> {
+        indexmap::IndexMap::get_mut(self, key)
+    }
+
+    fn remove(&mut self, key: &K) -> Option<V> {
+        indexmap::IndexMap::shift_remove(self, key)
+    }
+
+    fn iter(&self) -> Self::Iter<'_> {
+        indexmap::IndexMap::iter(self)
+    }
+
+    fn iter_mut(&mut self) -> Self::IterMut<'_> {
+        indexmap::IndexMap::iter_mut(self)
+    }
+
+    fn retain<F>(&mut self, f: F)
+    where
+        F: FnMut(&K, &mut V) -> bool,
+    {
+        indexmap::IndexMap::retain(self, f)
+    }
+
+    fn len(&self) -> usize {
+        indexmap::IndexMap::len(self)
+    }
+}
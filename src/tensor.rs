@@ -0,0 +1,89 @@
+//! Outer products of maps, and marginals over tuple keys.
+
+use std::ops::{AddAssign, Mul};
+
+use crate::{ArithMap, Storage};
+
+impl<K, V, S> ArithMap<K, V, S>
+where
+    S: Storage<K, V>,
+{
+    /// Map keyed by every pair of keys, holding the product of their values.
+    ///
+    /// ```
+    /// # use arith::*;
+    /// let x = arithmap!{"a" => 1, "b" => 2};
+    /// let y = arithmap!{0 => 3, 1 => 4};
+    /// let z: ArithMap<_, _> = x.outer(&y);
+    ///
+    /// assert_eq!(z, arithmap!{("a", 0) => 3, ("a", 1) => 4, ("b", 0) => 6, ("b", 1) => 8});
+    /// ```
+    pub fn outer<L, T, U>(&self, other: &ArithMap<L, V, T>) -> ArithMap<(K, L), V, U>
+    where
+        K: Clone,
+        L: Clone,
+        T: Storage<L, V>,
+        U: Storage<(K, L), V>,
+        for<'x> &'x V: Mul<Output = V>,
+    {
+        let mut storage = U::default();
+        for (k, v1) in self.storage.iter() {
+            for (l, v2) in other.storage.iter() {
+                storage.insert((k.clone(), l.clone()), v1 * v2);
+            }
+        }
+        ArithMap::from(storage)
+    }
+}
+
+impl<K, L, V, S> ArithMap<(K, L), V, S>
+where
+    S: Storage<(K, L), V>,
+{
+    /// Sums over the second component of every key.
+    ///
+    /// Marginals of an outer product give back the factors, each scaled by the total of the
+    /// other:
+    ///
+    /// ```
+    /// # use arith::*;
+    /// let x = arithmap!{"a" => 1, "b" => 2};
+    /// let y = arithmap!{0 => 3, 1 => 4};
+    /// let z: ArithMap<_, _> = x.outer(&y);
+    /// let first: ArithMap<_, _> = z.marginal_first();
+    ///
+    /// assert_eq!(first, x * y.total());
+    /// ```
+    pub fn marginal_first<T>(&self) -> ArithMap<K, V, T>
+    where
+        K: Clone,
+        V: AddAssign + Clone,
+        T: Storage<K, V>,
+    {
+        self.storage
+            .iter()
+            .map(|((k, _), v)| (k.clone(), v.clone()))
+            .collect()
+    }
+
+    /// Sums over the first component of every key.
+    ///
+    /// ```
+    /// # use arith::*;
+    /// let x = arithmap!{("a", 0) => 1, ("b", 0) => 2, ("b", 1) => 3};
+    /// let y: ArithMap<_, _> = x.marginal_second();
+    ///
+    /// assert_eq!(y, arithmap!{0 => 3, 1 => 3});
+    /// ```
+    pub fn marginal_second<T>(&self) -> ArithMap<L, V, T>
+    where
+        L: Clone,
+        V: AddAssign + Clone,
+        T: Storage<L, V>,
+    {
+        self.storage
+            .iter()
+            .map(|((_, l), v)| (l.clone(), v.clone()))
+            .collect()
+    }
+}
@@ -0,0 +1,128 @@
+//! General-purpose transforms that the operators are special cases of.
+
+use crate::{ArithMap, Join, Storage};
+
+impl<K, V, S> ArithMap<K, V, S>
+where
+    S: Storage<K, V>,
+{
+    /// Applies `f` to every value; the result may have a different value type, and so a
+    /// different storage.
+    ///
+    /// ```
+    /// # use arith::*;
+    /// let x = arithmap!{"a" => 1, "b" => 2};
+    /// let y: ArithMap<_, _> = x.map_values(|v| v as f64 / 2.0);
+    ///
+    /// assert_eq!(y, arithmap!{"a" => 0.5, "b" => 1.0});
+    /// ```
+    pub fn map_values<W, T, F>(self, mut f: F) -> ArithMap<K, W, T>
+    where
+        T: Storage<K, W>,
+        F: FnMut(V) -> W,
+    {
+        let mut storage = T::default();
+        for (k, v) in self.storage {
+            storage.insert(k, f(v));
+        }
+        ArithMap::from(storage)
+    }
+
+    /// Like [`ArithMap::combine`], but borrowing both maps.
+    ///
+    /// ```
+    /// # use arith::*;
+    /// let x = arithmap!{"a" => 1, "b" => 5};
+    /// let y = arithmap!{"b" => 3, "c" => 4};
+    ///
+    /// assert_eq!(x.zip_with(&y, Join::Inner, |v1, v2| v1.max(v2) * 10), arithmap!{"b" => 50});
+    /// assert_eq!(x.zip_with(&y, Join::Right(0), |v1, v2| v1.max(v2) * 10), arithmap!{"b" => 50, "c" => 40});
+    /// assert_eq!(
+    ///     x.zip_with(&y, Join::Outer { left: 0, right: 0 }, |v1, v2| v1.max(v2) * 10),
+    ///     arithmap!{"a" => 10, "b" => 50, "c" => 40},
+    /// );
+    /// ```
+    pub fn zip_with<F>(&self, other: &Self, join: Join<V>, mut f: F) -> Self
+    where
+        K: Clone,
+        F: FnMut(&V, &V) -> V,
+    {
+        let (left, right) = join.fills();
+        let mut storage = S::default();
+        for (k, v1) in self.storage.iter() {
+            match (other.storage.get(k), &right) {
+                (Some(v2), _) => storage.insert(k.clone(), f(v1, v2)),
+                (None, Some(fill)) => storage.insert(k.clone(), f(v1, fill)),
+                (None, None) => None,
+            };
+        }
+        if let Some(fill) = &left {
+            for (k, v2) in other.storage.iter() {
+                if self.storage.get(k).is_none() {
+                    storage.insert(k.clone(), f(fill, v2));
+                }
+            }
+        }
+        ArithMap::from(storage)
+    }
+
+    /// Keeps the entries whose value satisfies `pred`.
+    ///
+    /// ```
+    /// # use arith::*;
+    /// let x = arithmap!{"a" => 1, "b" => -2, "c" => 3};
+    ///
+    /// assert_eq!(x.filter(|v| *v > 0), arithmap!{"a" => 1, "c" => 3});
+    /// ```
+    pub fn filter<F>(mut self, mut pred: F) -> Self
+    where
+        F: FnMut(&V) -> bool,
+    {
+        self.storage.retain(|_, v| pred(v));
+        self.storage.tidy();
+        self
+    }
+
+    /// Keeps the entries whose key satisfies `pred`.
+    ///
+    /// ```
+    /// # use arith::*;
+    /// let x = arithmap!{"a" => 1, "b" => 2, "ab" => 3};
+    ///
+    /// assert_eq!(x.filter_keys(|k| k.starts_with('a')), arithmap!{"a" => 1, "ab" => 3});
+    /// ```
+    pub fn filter_keys<F>(mut self, mut pred: F) -> Self
+    where
+        F: FnMut(&K) -> bool,
+    {
+        self.storage.retain(|k, _| pred(k));
+        self.storage.tidy();
+        self
+    }
+
+    /// Splits into the entries that satisfy `pred` and those that don't.
+    ///
+    /// ```
+    /// # use arith::*;
+    /// let x = arithmap!{"a" => 1, "b" => -2, "c" => 3};
+    /// let (y, z) = x.partition(|_, v| *v > 0);
+    ///
+    /// assert_eq!(y, arithmap!{"a" => 1, "c" => 3});
+    /// assert_eq!(z, arithmap!{"b" => -2});
+    /// ```
+    pub fn partition<F>(self, mut pred: F) -> (Self, Self)
+    where
+        F: FnMut(&K, &V) -> bool,
+    {
+        let mut left = S::default();
+        let mut right = S::default();
+        for (k, v) in self.storage {
+            if pred(&k, &v) {
+                left.insert(k, v);
+            } else {
+                right.insert(k, v);
+            }
+        }
+        (ArithMap::from(left), ArithMap::from(right))
+    }
+}
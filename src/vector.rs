@@ -0,0 +1,175 @@
+//! Reductions treating an [`ArithMap`] as a sparse vector.
+
+use std::ops::{AddAssign, Mul};
+
+use crate::num::{Float, ToF64, Zero};
+use crate::{ArithMap, Storage};
+
+impl<K, V, S> ArithMap<K, V, S>
+where
+    S: Storage<K, V>,
+{
+    /// Iterates over the smaller of the two maps.
+    ///
+    /// ```
+    /// # use arith::*;
+    /// let x = arithmap!{"a" => 1, "b" => 2};
+    /// let y = arithmap!{"b" => 3, "c" => 4};
+    ///
+    /// assert_eq!(x.dot(&y), 6);
+    /// ```
+    pub fn dot(&self, other: &Self) -> V
+    where
+        V: AddAssign + Zero,
+        for<'x> &'x V: Mul<Output = V>,
+    {
+        let mut dot = V::zero();
+        for (v1, v2) in self.zip_smaller(other) {
+            dot += v1 * v2;
+        }
+        dot
+    }
+
+    /// ```
+    /// # use arith::*;
+    /// let x = arithmap!{"a" => 1, "b" => -2};
+    ///
+    /// assert_eq!(x.l1(), 3.0);
+    /// ```
+    pub fn l1(&self) -> f64
+    where
+        V: ToF64,
+    {
+        self.storage.iter().map(|(_, v)| v.to_f64().abs()).sum()
+    }
+
+    /// ```
+    /// # use arith::*;
+    /// let x = arithmap!{"a" => 3, "b" => -4};
+    ///
+    /// assert_eq!(x.l2(), 5.0);
+    /// ```
+    pub fn l2(&self) -> f64
+    where
+        V: ToF64,
+    {
+        self.storage
+            .iter()
+            .map(|(_, v)| v.to_f64().powi(2))
+            .sum::<f64>()
+            .sqrt()
+    }
+
+    /// ```
+    /// # use arith::*;
+    /// let x = arithmap!{"a" => 3, "b" => -4};
+    ///
+    /// assert_eq!(x.linf(), 4.0);
+    /// ```
+    pub fn linf(&self) -> f64
+    where
+        V: ToF64,
+    {
+        self.storage
+            .iter()
+            .map(|(_, v)| v.to_f64().abs())
+            .fold(0.0, f64::max)
+    }
+
+    /// Zero if either map is a zero vector.
+    ///
+    /// ```
+    /// # use arith::*;
+    /// let x = arithmap!{"a" => 1, "b" => 1};
+    /// let y = arithmap!{"a" => 2, "b" => 2};
+    /// let z = arithmap!{"c" => 5};
+    ///
+    /// assert!((x.cosine_similarity(&y) - 1.0).abs() < 1e-12);
+    /// assert_eq!(x.cosine_similarity(&z), 0.0);
+    /// ```
+    pub fn cosine_similarity(&self, other: &Self) -> f64
+    where
+        V: ToF64,
+    {
+        let norm = self.l2() * other.l2();
+        if norm == 0.0 {
+            return 0.0;
+        }
+        let dot: f64 = self
+            .zip_smaller(other)
+            .map(|(v1, v2)| v1.to_f64() * v2.to_f64())
+            .sum();
+        dot / norm
+    }
+
+    /// Euclidean distance, treating missing keys as zero.
+    ///
+    /// ```
+    /// # use arith::*;
+    /// let x = arithmap!{"a" => 1, "b" => 2};
+    /// let y = arithmap!{"b" => 5, "c" => 4};
+    ///
+    /// assert_eq!(x.distance(&y), 26.0f64.sqrt());
+    /// ```
+    pub fn distance(&self, other: &Self) -> f64
+    where
+        V: ToF64,
+    {
+        let left: f64 = self
+            .storage
+            .iter()
+            .map(|(k, v1)| {
+                let v2 = other.storage.get(k).map_or(0.0, ToF64::to_f64);
+                (v1.to_f64() - v2).powi(2)
+            })
+            .sum();
+        let right: f64 = other
+            .storage
+            .iter()
+            .filter(|(k, _)| self.storage.get(k).is_none())
+            .map(|(_, v2)| v2.to_f64().powi(2))
+            .sum();
+        (left + right).sqrt()
+    }
+
+    /// Pairs of values under keys present in both maps.
+    fn zip_smaller<'a>(&'a self, other: &'a Self) -> impl Iterator<Item = (&'a V, &'a V)> + 'a {
+        let (small, large, swapped) = if self.storage.len() <= other.storage.len() {
+            (self, other, false)
+        } else {
+            (other, self, true)
+        };
+        small.storage.iter().filter_map(move |(k, v1)| {
+            large
+                .storage
+                .get(k)
+                .map(|v2| if swapped { (v2, v1) } else { (v1, v2) })
+        })
+    }
+}
+
+impl<K, V, S> ArithMap<K, V, S>
+where
+    S: Storage<K, V>,
+    V: Float,
+{
+    /// Scales to unit `l2` norm; a zero vector is left unchanged.
+    ///
+    /// ```
+    /// # use arith::*;
+    /// let mut x = arithmap!{"a" => 3.0, "b" => -4.0};
+    /// x.normalize();
+    ///
+    /// assert_eq!(x, arithmap!{"a" => 0.6, "b" => -0.8});
+    /// ```
+    pub fn normalize(&mut self) {
+        let norm = self.l2();
+        if norm != 0.0 {
+            let norm = V::from_f64(norm);
+            for (_, v) in self.storage.iter_mut() {
+                *v /= norm;
+            }
+            self.storage.tidy();
+        }
+    }
+}